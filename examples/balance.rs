@@ -0,0 +1,287 @@
+use std::env;
+
+use bevy_game::{
+    simulate_battle, BattleConfig, BattleModifiers, Campaign, Item, ItemDefinitions, Quest, Team,
+    UnitComposition, UnitDefinitions, UnitKind,
+};
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+// usage: cargo run --example balance -- --army 10,2,4,0,0 --items AxeShrooms --runs 50
+//        cargo run --example balance -- --army 30,6,10,2,1 --budget 40 --win-rate 0.8 --format json
+struct Options {
+    army: UnitComposition,
+    friendly_modifiers: BattleModifiers,
+    runs: u64,
+    seed: u64,
+    war_chef: Option<usize>,
+    budget: Option<usize>,
+    win_rate: f32,
+    json: bool,
+}
+
+impl Options {
+    fn parse() -> Self {
+        let mut options = Options {
+            army: UnitComposition::empty(),
+            friendly_modifiers: BattleModifiers::default(),
+            runs: 20,
+            seed: 0,
+            war_chef: None,
+            budget: None,
+            win_rate: 0.8,
+            json: false,
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .unwrap_or_else(|| panic!("missing value for {}", arg))
+            };
+            match arg.as_str() {
+                "--army" => {
+                    let value = value();
+                    for (unit_kind, count) in UnitKind::iter().zip(value.split(',')) {
+                        options
+                            .army
+                            .set_count(unit_kind, count.trim().parse().expect("invalid army"));
+                    }
+                }
+                "--items" => {
+                    let item_definitions = ItemDefinitions::default();
+                    for name in value().split(',') {
+                        let item = Item::new(name.trim());
+                        for modifier in item_definitions
+                            .get(&item)
+                            .unwrap_or_else(|| panic!("unknown item {}", name))
+                            .modifiers
+                            .iter()
+                        {
+                            options.friendly_modifiers.add_stack(*modifier);
+                        }
+                    }
+                }
+                "--runs" => options.runs = value().parse().expect("invalid runs"),
+                "--seed" => options.seed = value().parse().expect("invalid seed"),
+                "--war-chef" => options.war_chef = Some(value().parse().expect("invalid war chef")),
+                "--budget" => options.budget = Some(value().parse().expect("invalid budget")),
+                "--win-rate" => options.win_rate = value().parse().expect("invalid win rate"),
+                "--format" => options.json = value() == "json",
+                _ => panic!("unknown argument {}", arg),
+            }
+        }
+        options
+    }
+}
+
+#[derive(Serialize)]
+struct BalanceRow {
+    war_chef: usize,
+    battle: usize,
+    food: usize,
+    peasants: usize,
+    warriors: usize,
+    archers: usize,
+    mages: usize,
+    brutes: usize,
+    runs: u64,
+    win_rate: f32,
+    average_duration: f32,
+    dead_peasants: f32,
+    dead_warriors: f32,
+    dead_archers: f32,
+    dead_mages: f32,
+    dead_brutes: f32,
+}
+
+impl BalanceRow {
+    fn csv_header() -> &'static str {
+        "war_chef,battle,food,peasants,warriors,archers,mages,brutes,runs,win_rate,average_duration,dead_peasants,dead_warriors,dead_archers,dead_mages,dead_brutes"
+    }
+
+    fn csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{:.3},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2}",
+            self.war_chef,
+            self.battle,
+            self.food,
+            self.peasants,
+            self.warriors,
+            self.archers,
+            self.mages,
+            self.brutes,
+            self.runs,
+            self.win_rate,
+            self.average_duration,
+            self.dead_peasants,
+            self.dead_warriors,
+            self.dead_archers,
+            self.dead_mages,
+            self.dead_brutes,
+        )
+    }
+}
+
+fn main() {
+    let options = Options::parse();
+    let unit_definitions = UnitDefinitions::default();
+    let campaign = Campaign::default();
+    let mut rows = vec![];
+    for quest in quests(&campaign) {
+        if options
+            .war_chef
+            .map_or(false, |war_chef| war_chef != quest.war_chef)
+        {
+            continue;
+        }
+        let row = if let Some(budget) = options.budget {
+            feedings(&unit_definitions, &options.army, budget)
+                .into_iter()
+                .map(|army| run_battles(&unit_definitions, &campaign, &options, &quest, &army))
+                .find(|row| row.win_rate >= options.win_rate)
+        } else {
+            Some(run_battles(
+                &unit_definitions,
+                &campaign,
+                &options,
+                &quest,
+                &options.army,
+            ))
+        };
+        match row {
+            Some(row) => rows.push(row),
+            None => eprintln!(
+                "no affordable army wins war chef {} battle {} at least {}% of the time",
+                quest.war_chef,
+                quest.battle,
+                options.win_rate * 100.
+            ),
+        }
+    }
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&rows).unwrap());
+    } else {
+        println!("{}", BalanceRow::csv_header());
+        for row in rows.iter() {
+            println!("{}", row.csv());
+        }
+    }
+}
+
+fn quests(campaign: &Campaign) -> Vec<Quest> {
+    let mut quests = vec![];
+    let mut quest = Quest::default();
+    loop {
+        quests.push(quest.clone());
+        if !quest.next(campaign) {
+            break;
+        }
+    }
+    quests
+}
+
+fn food_cost(unit_definitions: &UnitDefinitions, army: &UnitComposition) -> usize {
+    UnitKind::iter()
+        .map(|unit_kind| army.get_count(unit_kind) * unit_definitions.unit_stats(unit_kind).cost)
+        .sum()
+}
+
+// every feeding of the available army that fits the budget, cheapest first
+fn feedings(
+    unit_definitions: &UnitDefinitions,
+    available: &UnitComposition,
+    budget: usize,
+) -> Vec<UnitComposition> {
+    fn recurse(
+        unit_definitions: &UnitDefinitions,
+        available: &UnitComposition,
+        budget: usize,
+        unit_kinds: &[UnitKind],
+        army: &mut UnitComposition,
+        feedings: &mut Vec<UnitComposition>,
+    ) {
+        let Some((unit_kind, rest)) = unit_kinds.split_first() else {
+            if army.total_units() > 0 {
+                feedings.push(army.clone());
+            }
+            return;
+        };
+        let cost = unit_definitions.unit_stats(*unit_kind).cost;
+        for count in 0..=available.get_count(*unit_kind) {
+            if count * cost > budget {
+                break;
+            }
+            army.set_count(*unit_kind, count);
+            recurse(
+                unit_definitions,
+                available,
+                budget - count * cost,
+                rest,
+                army,
+                feedings,
+            );
+        }
+        army.set_count(*unit_kind, 0);
+    }
+    let unit_kinds: Vec<UnitKind> = UnitKind::iter().collect();
+    let mut feedings = vec![];
+    recurse(
+        unit_definitions,
+        available,
+        budget,
+        &unit_kinds,
+        &mut UnitComposition::empty(),
+        &mut feedings,
+    );
+    feedings.sort_by_key(|army| food_cost(unit_definitions, army));
+    feedings
+}
+
+fn run_battles(
+    unit_definitions: &UnitDefinitions,
+    campaign: &Campaign,
+    options: &Options,
+    quest: &Quest,
+    army: &UnitComposition,
+) -> BalanceRow {
+    let config = BattleConfig {
+        friendly_units: army.clone(),
+        friendly_modifiers: options.friendly_modifiers.clone(),
+        enemy_units: quest.enemy_unit_composition(campaign),
+        enemy_modifiers: quest.enemy_modifiers(campaign),
+        enemy_banner: quest.enemy_banner(campaign),
+        victory_conditions: quest.victory_conditions(campaign),
+        ..Default::default()
+    };
+    let mut victories = 0;
+    let mut duration = 0.;
+    let mut dead_units = UnitComposition::empty();
+    for run in 0..options.runs {
+        let report = simulate_battle(config.clone(), options.seed.wrapping_add(run));
+        if report.victory() {
+            victories += 1;
+        }
+        duration += report.duration;
+        dead_units.add_units(&report.team(Team::Friendly).killed());
+    }
+    let runs = options.runs.max(1) as f32;
+    let average_dead = |unit_kind: UnitKind| dead_units.get_count(unit_kind) as f32 / runs;
+    BalanceRow {
+        war_chef: quest.war_chef,
+        battle: quest.battle,
+        food: food_cost(unit_definitions, army),
+        peasants: army.peasants,
+        warriors: army.warriors,
+        archers: army.archers,
+        mages: army.mages,
+        brutes: army.brutes,
+        runs: options.runs,
+        win_rate: victories as f32 / runs,
+        average_duration: duration / runs,
+        dead_peasants: average_dead(UnitKind::Peasant),
+        dead_warriors: average_dead(UnitKind::Warrior),
+        dead_archers: average_dead(UnitKind::Archer),
+        dead_mages: average_dead(UnitKind::Mage),
+        dead_brutes: average_dead(UnitKind::Brute),
+    }
+}
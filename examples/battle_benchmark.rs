@@ -0,0 +1,48 @@
+use std::time::{Duration, Instant};
+
+use bevy_game::{BattleConfig, BattleSimulation, UnitComposition};
+
+// usage: cargo run --release --example battle_benchmark
+const TICKS: usize = 120 * 10;
+const TICK_BUDGET: Duration = Duration::from_nanos(1_000_000_000 / 120);
+
+fn main() {
+    let army = UnitComposition {
+        peasants: 400,
+        warriors: 250,
+        archers: 200,
+        mages: 100,
+        brutes: 50,
+    };
+    let config = BattleConfig {
+        friendly_units: army.clone(),
+        enemy_units: army,
+        ..Default::default()
+    };
+
+    let mut simulation = BattleSimulation::new(config, 0);
+    let mut tick_times = vec![];
+    for _ in 0..TICKS {
+        let start = Instant::now();
+        let report = simulation.tick();
+        tick_times.push(start.elapsed());
+        if report.is_some() {
+            break;
+        }
+    }
+
+    tick_times.sort();
+    let total: Duration = tick_times.iter().sum();
+    let average = total / tick_times.len() as u32;
+    let p99 = tick_times[tick_times.len() * 99 / 100];
+    let max = tick_times[tick_times.len() - 1];
+    println!("1000 vs 1000 units, {} ticks", tick_times.len());
+    println!("average: {:?}", average);
+    println!("p99:     {:?}", p99);
+    println!("max:     {:?}", max);
+    println!("budget:  {:?}", TICK_BUDGET);
+    if average > TICK_BUDGET {
+        eprintln!("average tick time is over budget");
+        std::process::exit(1);
+    }
+}
@@ -8,9 +8,9 @@ use bevy_egui::{
     EguiContexts,
 };
 use bevy_game::{
-    cleanup_non_persistent_entities, AssetLibraryPlugin, BattleConfig, BattleModifier,
-    BattleModifiers, BattleStartEvent, CommonPlugins, EventSet, GamePlugins, Persistent,
-    UnitComposition, UnitKind,
+    cleanup_non_persistent_entities, replay_ui, AssetLibraryPlugin, BattleConfig, BattleModifier,
+    BattleModifiers, BattleRng, BattleStartEvent, CommonPlugins, EventSet, GamePlugins, Persistent,
+    ReplayState, UnitComposition, UnitKind,
 };
 use strum::IntoEnumIterator;
 
@@ -24,16 +24,24 @@ fn main() {
     App::new()
         .insert_resource(Msaa::Off)
         .insert_resource(ClearColor(Color::rgb(0.1, 0.1, 0.1)))
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Battle Sandbox".to_owned(),
-                resolution: (1280., 768.).into(),
-                canvas: Some("#bevy".to_owned()),
-                fit_canvas_to_parent: true,
-                ..default()
-            }),
-            ..default()
-        }))
+        .add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Battle Sandbox".to_owned(),
+                        resolution: (1280., 768.).into(),
+                        canvas: Some("#bevy".to_owned()),
+                        fit_canvas_to_parent: true,
+                        ..default()
+                    }),
+                    ..default()
+                })
+                .set(AssetPlugin {
+                    // lets the unit stats be tweaked while the game is running
+                    watch_for_changes: true,
+                    ..default()
+                }),
+        )
         .add_plugin(AssetLibraryPlugin)
         .add_plugins(CommonPlugins)
         .add_plugins(GamePlugins)
@@ -41,6 +49,7 @@ fn main() {
             cleanup: false,
             start_battle: false,
             battle_config,
+            replay_error: None,
         })
         .add_system(setup.in_schedule(CoreSchedule::Startup))
         .add_system(pre_update.in_base_set(CoreSet::PreUpdate))
@@ -63,6 +72,7 @@ pub struct ExampleState {
     cleanup: bool,
     start_battle: bool,
     battle_config: BattleConfig,
+    replay_error: Option<String>,
 }
 
 fn setup(mut commands: Commands) {
@@ -90,7 +100,11 @@ fn fixed_update(
     }
 }
 
-fn ui(mut contexts: EguiContexts, mut example_state: ResMut<ExampleState>) {
+fn ui(
+    mut contexts: EguiContexts,
+    mut example_state: ResMut<ExampleState>,
+    mut replay_state: ResMut<ReplayState>,
+) {
     egui::Window::new("Battle").show(contexts.ctx_mut(), |ui| {
         fn unit_composition_ui(ui: &mut Ui, unit_composition: &mut UnitComposition) {
             for unit_kind in UnitKind::iter() {
@@ -110,9 +124,12 @@ fn ui(mut contexts: EguiContexts, mut example_state: ResMut<ExampleState>) {
         }
         fn battle_modifiers_ui(ui: &mut Ui, battle_modifiers: &mut BattleModifiers) {
             for battle_modifier in BattleModifier::iter() {
-                let mut checked = battle_modifiers[battle_modifier];
-                ui.checkbox(&mut checked, battle_modifier.name());
-                battle_modifiers[battle_modifier] = checked;
+                ui.horizontal(|ui| {
+                    let mut stacks = battle_modifiers.get_stacks(battle_modifier);
+                    ui.add(egui::DragValue::new(&mut stacks).clamp_range(0..=5));
+                    battle_modifiers.set_stacks(battle_modifier, stacks);
+                    ui.label(battle_modifier.name());
+                });
             }
         }
 
@@ -141,6 +158,16 @@ fn ui(mut contexts: EguiContexts, mut example_state: ResMut<ExampleState>) {
 
         ui.add_space(16.);
 
+        ui.horizontal(|ui| {
+            ui.label("Seed");
+            ui.add(egui::DragValue::new(&mut example_state.battle_config.seed));
+            if ui.button("Randomize").clicked() {
+                example_state.battle_config.seed = BattleRng::random_seed();
+            }
+        });
+
+        ui.add_space(16.);
+
         ui.horizontal(|ui| {
             if ui.button("Save").clicked() {
                 write(
@@ -154,6 +181,15 @@ fn ui(mut contexts: EguiContexts, mut example_state: ResMut<ExampleState>) {
                 example_state.battle_config = BattleConfig::default();
             }
         });
+
+        ui.add_space(16.);
+
+        if let Some(battle_config) =
+            replay_ui(ui, &mut replay_state, &mut example_state.replay_error)
+        {
+            example_state.battle_config = battle_config;
+            example_state.cleanup = true;
+        }
     });
     egui::Window::new("Start").show(contexts.ctx_mut(), |ui| {
         if ui.button("Start Battle").clicked() {
@@ -83,7 +83,7 @@ fn events(mut dialogue_events: EventReader<DialogueEvent>) {
                 println!("Subtract {} food", amount);
             }
             ArticyDialogueInstruction::AddItem(item) => {
-                println!("Add item: {}", item.name());
+                println!("Add item: {}", item);
             }
             ArticyDialogueInstruction::SetGlobalVariable(name, value) => {
                 println!("Set global variable: {} {:?}", name, value);
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_spine::SkeletonData;
 
-use crate::Sounds;
+use crate::{Campaign, ItemDefinitions, Sounds, UnitDefinitions};
 
 #[derive(Resource, Default)]
 pub struct AssetLibrary {
@@ -41,6 +41,10 @@ pub struct AssetLibrary {
     pub spine_outro: Handle<SkeletonData>,
     pub spine_banner: Handle<SkeletonData>,
 
+    pub units: Handle<UnitDefinitions>,
+    pub campaign: Handle<Campaign>,
+    pub items: Handle<ItemDefinitions>,
+
     pub sounds: Sounds,
 }
 
@@ -225,5 +229,9 @@ fn asset_library_load(
         asset_server.load("spines/banner/banner.atlas"),
     ));
 
+    asset_library.units = asset_server.load("rattoria.units.ron");
+    asset_library.campaign = asset_server.load("campaigns/rattoria.campaign.ron");
+    asset_library.items = asset_server.load("spices.items.ron");
+
     asset_library.sounds = Sounds::setup(asset_server.as_ref());
 }
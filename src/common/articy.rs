@@ -4,7 +4,7 @@ use anyhow::{bail, Context, Result};
 use bevy::prelude::*;
 use serde_json::Value;
 
-use crate::{Item, Speaker, UnitKind};
+use crate::{Speaker, UnitKind};
 
 pub struct ArticyPlugin;
 
@@ -63,7 +63,7 @@ pub enum ArticyDialogueInstruction {
     SubtractUnits(UnitKind, usize),
     AddFood(usize),
     SubtractFood(usize),
-    AddItem(Item),
+    AddItem(String),
     SetGlobalVariable(String, bool),
 }
 
@@ -351,17 +351,7 @@ fn parse_instructions(str: &str) -> Vec<ArticyDialogueInstruction> {
                     },
                     "AddItem" => match params.get(0) {
                         Some(Param::String(name)) => {
-                            let item = match name.as_str() {
-                                "Crackling Moss" => Item::CracklingMoss,
-                                "Squirt Blop-Berries" => Item::SquirtBlopBerries,
-                                "Firemander Salts" => Item::FiremanderSalts,
-                                "Axe Shrooms" => Item::AxeShrooms,
-                                "Bog Hard-Weeds" => Item::BogHardWeeds,
-                                "Celery Quartz" => Item::CeleryQuartz,
-                                "Frosty Web-Strands" => Item::FrostyWebStrands,
-                                _ => panic!("unknown AddItem() name: {}", name),
-                            };
-                            instructions.push(ArticyDialogueInstruction::AddItem(item));
+                            instructions.push(ArticyDialogueInstruction::AddItem(name.clone()));
                         }
                         _ => panic!(
                             "wrong parameters to articy function: {} {:?}",
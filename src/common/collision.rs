@@ -29,6 +29,19 @@ pub struct TranslatedCollisionShape {
 }
 
 impl TranslatedCollisionShape {
+    pub fn bounds(&self) -> Option<(Vec2, Vec2)> {
+        match self.shape {
+            CollisionShape::None => None,
+            CollisionShape::Point { offset } => {
+                Some((self.translation + offset, self.translation + offset))
+            }
+            CollisionShape::Rect { offset, size } => Some((
+                self.translation + offset - size * 0.5,
+                self.translation + offset + size * 0.5,
+            )),
+        }
+    }
+
     pub fn overlaps(&self, other: TranslatedCollisionShape) -> bool {
         match self.shape {
             CollisionShape::None => false,
@@ -67,4 +80,76 @@ impl TranslatedCollisionShape {
             },
         }
     }
+
+    // how far along delta this shape first touches the other one, both treated as boxes
+    pub fn sweep(&self, delta: Vec2, other: TranslatedCollisionShape) -> Option<f32> {
+        let (min, max) = self.bounds()?;
+        let (other_min, other_max) = other.bounds()?;
+        // grow the other box by this one so the sweep becomes a ray from our centre
+        let half_size = (max - min) * 0.5;
+        let origin = (min + max) * 0.5;
+        let box_min = other_min - half_size;
+        let box_max = other_max + half_size;
+        let mut enter: f32 = 0.;
+        let mut exit: f32 = 1.;
+        for axis in 0..2 {
+            if delta[axis] == 0. {
+                if origin[axis] < box_min[axis] || origin[axis] > box_max[axis] {
+                    return None;
+                }
+            } else {
+                let a = (box_min[axis] - origin[axis]) / delta[axis];
+                let b = (box_max[axis] - origin[axis]) / delta[axis];
+                enter = enter.max(a.min(b));
+                exit = exit.min(a.max(b));
+            }
+        }
+        (enter <= exit).then_some(enter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(translation: Vec2, size: Vec2) -> TranslatedCollisionShape {
+        CollisionShape::Rect {
+            offset: Vec2::ZERO,
+            size,
+        }
+        .at(translation)
+    }
+
+    #[test]
+    fn sweep_finds_the_first_touch_along_the_path() {
+        let arrow = rect(Vec2::ZERO, Vec2::new(10., 10.));
+        let target = rect(Vec2::new(100., 0.), Vec2::new(50., 50.));
+        // the boxes touch once the arrow's front reaches the target's back, 70 of the 200 moved
+        let impact_time = arrow.sweep(Vec2::new(200., 0.), target).unwrap();
+        assert!((impact_time - 0.35).abs() < 0.0001);
+        // a path that stops short or passes above never touches
+        assert!(arrow.sweep(Vec2::new(50., 0.), target).is_none());
+        assert!(arrow
+            .sweep(
+                Vec2::new(200., 0.),
+                rect(Vec2::new(100., 100.), Vec2::new(50., 50.))
+            )
+            .is_none());
+        // already overlapping touches straight away
+        assert_eq!(
+            arrow.sweep(Vec2::new(200., 0.), rect(Vec2::ZERO, Vec2::new(50., 50.))),
+            Some(0.)
+        );
+    }
+
+    #[test]
+    fn sweep_catches_a_falling_path_through_a_box() {
+        let arrow = rect(Vec2::new(0., 100.), Vec2::new(10., 10.));
+        let target = rect(Vec2::new(40., 0.), Vec2::new(40., 40.));
+        // too fast to overlap at either end of the tick, but the path crosses the box
+        assert!(!arrow.overlaps(target));
+        assert!(!rect(Vec2::new(80., -100.), Vec2::new(10., 10.)).overlaps(target));
+        assert!(arrow.sweep(Vec2::new(80., -200.), target).is_some());
+        assert!(arrow.sweep(Vec2::ZERO, target).is_none());
+    }
 }
@@ -30,6 +30,7 @@ pub struct DebugDrawSettings {
     pub draw_hit_boxes: bool,
     pub draw_hurt_boxes: bool,
     pub draw_feelers: bool,
+    pub draw_area_of_effect_targets: bool,
 }
 
 #[derive(Resource, Default)]
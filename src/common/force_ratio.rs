@@ -1,23 +1,52 @@
 use bevy::{prelude::*, transform::TransformSystem};
 
-use crate::Persistent;
+use crate::{Persistent, Transform2, Transform2System};
 
-const DESIRED_WIDTH: f32 = 1280. * 2.;
-const DESIRED_HEIGHT: f32 = 768. * 2.;
+pub const DESIRED_WIDTH: f32 = 1280. * 2.;
+pub const DESIRED_HEIGHT: f32 = 768. * 2.;
 const RATIO_BAR_SIZE: f32 = 100_000.;
 
 pub struct ForceRatioPlugin;
 
 impl Plugin for ForceRatioPlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(force_ratio_setup).add_system(
-            force_ratio_update
-                .in_base_set(CoreSet::PostUpdate)
-                .before(TransformSystem::TransformPropagate),
-        );
+        app.init_resource::<CameraView>()
+            .add_startup_system(force_ratio_setup)
+            .add_system(
+                force_ratio_update
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_system(
+                camera_anchor_update
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(Transform2System::Transform2Propagate),
+            );
     }
 }
 
+// the area of the world the camera looks at, zoom scales the letterboxed area
+#[derive(Resource)]
+pub struct CameraView {
+    pub position: Vec2,
+    pub zoom: f32,
+}
+
+impl Default for CameraView {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            zoom: 1.,
+        }
+    }
+}
+
+// keeps an entity at a fixed spot on screen regardless of the camera view
+#[derive(Component)]
+pub struct CameraAnchor {
+    pub offset: Vec2,
+}
+
 #[derive(Component, Clone, Copy)]
 pub enum ForceRatioBar {
     Top,
@@ -68,6 +97,7 @@ fn force_ratio_update(
     camera_query: Query<Entity, With<Camera>>,
     bar_query: Query<(Entity, &ForceRatioBar)>,
     window_query: Query<&Window>,
+    camera_view: Res<CameraView>,
 ) {
     if let Ok(window) = window_query.get_single() {
         for camera_entity in camera_query.iter() {
@@ -81,14 +111,28 @@ fn force_ratio_update(
                 } else {
                     desired_height *= desired_ratio / ratio;
                 }
-                camera_transform.scale.x = desired_width / window.width();
-                camera_transform.scale.y = desired_height / window.height();
+                camera_transform.scale.x = desired_width / window.width() * camera_view.zoom;
+                camera_transform.scale.y = desired_height / window.height() * camera_view.zoom;
+                camera_transform.translation.x = camera_view.position.x;
+                camera_transform.translation.y = camera_view.position.y;
             }
         }
     }
     for (bar_entity, bar) in bar_query.iter() {
         if let Ok(mut bar_transform) = transform_query.get_mut(bar_entity) {
-            bar_transform.translation = bar.translation();
+            bar_transform.translation = camera_view.position.extend(0.)
+                + bar.translation() * Vec3::new(camera_view.zoom, camera_view.zoom, 1.);
+            bar_transform.scale = Vec3::new(camera_view.zoom, camera_view.zoom, 1.);
         }
     }
 }
+
+fn camera_anchor_update(
+    mut anchor_query: Query<(&mut Transform2, &CameraAnchor)>,
+    camera_view: Res<CameraView>,
+) {
+    for (mut transform, anchor) in anchor_query.iter_mut() {
+        transform.translation = camera_view.position + anchor.offset * camera_view.zoom;
+        transform.scale = Vec2::splat(camera_view.zoom);
+    }
+}
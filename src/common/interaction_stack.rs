@@ -14,6 +14,7 @@ impl Plugin for InteractionStackPlugin {
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Enum, EnumIter)]
 pub enum InteractionMode {
     Dialogue,
+    Formation,
     #[default]
     Game,
 }
@@ -26,7 +27,7 @@ pub struct InteractionStack {
 impl Default for InteractionStack {
     fn default() -> Self {
         Self {
-            wants_interaction: enum_map! { _ => true },
+            wants_interaction: enum_map! { InteractionMode::Formation => false, _ => true },
         }
     }
 }
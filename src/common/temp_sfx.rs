@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_audio_plus::{prelude::AudioPlusSoundEffect, source::AudioPlusSource};
 
-use crate::{Persistent, Transform2};
+use crate::{BattleTime, Persistent, Transform2};
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
 pub enum TempSfxSystem {
@@ -31,9 +31,13 @@ fn temp_sfx_update(
     mut temp_sfx_query: Query<(Entity, &mut TempSfx)>,
     mut commands: Commands,
     time: Res<Time>,
+    battle_time: Option<Res<BattleTime>>,
 ) {
+    let time_scale = battle_time
+        .map(|battle_time| battle_time.time_scale())
+        .unwrap_or(1.);
     for (temp_sfx_entity, mut temp_sfx) in temp_sfx_query.iter_mut() {
-        temp_sfx.time_to_live -= time.delta_seconds();
+        temp_sfx.time_to_live -= time.delta_seconds() * time_scale;
         if temp_sfx.time_to_live < 0. {
             if let Some(entity) = commands.get_entity(temp_sfx_entity) {
                 entity.despawn_recursive();
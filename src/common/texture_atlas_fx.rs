@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::BattleTime;
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
 pub enum TextureAtlasFxSystem {
     Update,
@@ -29,11 +31,15 @@ fn texture_atlas_fx_update(
     mut texture_atlas_fx_query: Query<(Entity, &mut TextureAtlasFx, &mut TextureAtlasSprite)>,
     mut commands: Commands,
     time: Res<Time>,
+    battle_time: Option<Res<BattleTime>>,
 ) {
+    let time_scale = battle_time
+        .map(|battle_time| battle_time.time_scale())
+        .unwrap_or(1.);
     for (texture_atlas_fx_entity, mut texture_atlas_fx, mut texture_atlas_fx_sprite) in
         texture_atlas_fx_query.iter_mut()
     {
-        texture_atlas_fx.frame += time.delta_seconds() * 15.;
+        texture_atlas_fx.frame += time.delta_seconds() * time_scale * 15.;
         let frame_int = texture_atlas_fx.frame as usize;
         if frame_int < texture_atlas_fx.frames {
             texture_atlas_fx_sprite.index = frame_int;
@@ -1,23 +1,28 @@
-use std::mem::take;
+use std::{collections::HashMap, mem::take};
 
 use bevy::prelude::*;
-use enum_map::{Enum, EnumMap};
+use enum_map::{enum_map, Enum, EnumMap};
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 use crate::{
-    AddFixedEvent, BannerSpawnEvent, BattleSplashEndedEvent, BattleSplashKind,
-    BattleSplashPlayEvent, BattleSplashSpawnEvent, BattlefieldSpawnEvent, DamageReceiveEvent,
-    EventSet, HealthDieEvent, Sfx, SfxKind, SpawnSet, Team, Unit, UnitKind, UnitSpawnEvent,
-    UpdateSet,
+    AddFixedEvent, BannerSpawnEvent, BattleOutcome, BattleRng, BattleSplashEndedEvent,
+    BattleSplashKind, BattleSplashPlayEvent, BattleSplashSpawnEvent, BattlefieldSpawnEvent,
+    DamageCause, DamageKind, DamageReceiveEvent, DefenseModifier, DefenseModifiers, EventSet,
+    Formation, Health, HealthDieEvent, MoraleEvent, MoraleEventKind, Sfx, SfxKind, SpawnSet,
+    StatusEffects, Team, Unit, UnitDefinitions, UnitKind, UnitSpawnEvent, UpdateSet,
+    VictoryCondition, VictoryContext, BANNER_CAPTURE_DISTANCE, SPAWN_X_DISTANCE, SPAWN_Y_MAX,
+    SPAWN_Y_MIN,
 };
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
 pub enum BattleSystem {
     Start,
+    Report,
     UnitDie,
+    Morale,
     EndDetection,
     SplashEnded,
 }
@@ -40,6 +45,13 @@ impl Plugin for BattlePlugin {
                     .in_set(EventSet::<BannerSpawnEvent>::Sender)
                     .after(EventSet::<BattleStartEvent>::Sender),
             )
+            .add_system(
+                battle_report_damage
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(BattleSystem::Report)
+                    .in_set(UpdateSet)
+                    .after(EventSet::<DamageReceiveEvent>::Sender),
+            )
             .add_system(
                 battle_unit_die
                     .in_schedule(CoreSchedule::FixedUpdate)
@@ -47,6 +59,13 @@ impl Plugin for BattlePlugin {
                     .in_set(UpdateSet)
                     .after(EventSet::<HealthDieEvent>::Sender),
             )
+            .add_system(
+                battle_report_morale
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(BattleSystem::Morale)
+                    .in_set(UpdateSet)
+                    .after(EventSet::<MoraleEvent>::Sender),
+            )
             .add_system(
                 battle_end_detection
                     .in_schedule(CoreSchedule::FixedUpdate)
@@ -71,11 +90,14 @@ pub struct BattleState {
     battling: bool,
     phase: BattlePhase,
     report: BattleReport,
+    last_report: Option<BattleReport>,
     end_timer: f32,
     damage_inflicted: bool,
     time_since_last_damage: f32,
     friendly_modifiers: BattleModifiers,
+    friendly_unit_modifiers: EnumMap<UnitKind, BattleModifiers>,
     enemy_modifiers: BattleModifiers,
+    victory_conditions: Vec<VictoryCondition>,
 }
 
 impl BattleState {
@@ -88,7 +110,7 @@ impl BattleState {
 pub enum BattlePhase {
     PreBattle,
     Battling,
-    End { victory: bool },
+    End { outcome: BattleOutcome },
     Results,
 }
 
@@ -98,11 +120,14 @@ impl Default for BattleState {
             battling: false,
             phase: BattlePhase::PreBattle,
             report: BattleReport::default(),
+            last_report: None,
             end_timer: 0.,
             damage_inflicted: false,
             time_since_last_damage: 0.,
             friendly_modifiers: BattleModifiers::default(),
+            friendly_unit_modifiers: EnumMap::default(),
             enemy_modifiers: BattleModifiers::default(),
+            victory_conditions: vec![],
         }
     }
 }
@@ -122,22 +147,234 @@ impl BattleState {
             Team::Enemy => &self.enemy_modifiers,
         }
     }
+
+    pub fn get_unit_modifiers(&self, team: Team, unit_kind: UnitKind) -> BattleModifiers {
+        let mut modifiers = *self.get_modifiers(team);
+        if team == Team::Friendly {
+            modifiers.add_stacks(&self.friendly_unit_modifiers[unit_kind]);
+        }
+        modifiers
+    }
+
+    pub fn report(&self) -> &BattleReport {
+        &self.report
+    }
+
+    pub fn last_report(&self) -> Option<&BattleReport> {
+        self.last_report.as_ref()
+    }
+
+    pub fn victory_conditions(&self) -> &[VictoryCondition] {
+        &self.victory_conditions
+    }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone, PartialEq)]
 pub struct BattleReport {
-    pub dead_units: UnitComposition,
-    pub victory: bool,
+    pub outcome: BattleOutcome,
+    pub duration: f32,
+    pub ticks: usize,
+    pub teams: EnumMap<Team, TeamReport>,
+}
+
+impl BattleReport {
+    pub fn victory(&self) -> bool {
+        self.outcome.victory()
+    }
+
+    pub fn team(&self, team: Team) -> &TeamReport {
+        &self.teams[team]
+    }
+
+    fn unit_mut(&mut self, team: Team, unit_kind: UnitKind) -> &mut UnitReport {
+        &mut self.teams[team].units[unit_kind]
+    }
+
+    pub fn count_survivors<'a>(
+        &mut self,
+        units: impl IntoIterator<Item = (&'a Unit, &'a Health, &'a StatusEffects)>,
+    ) {
+        for (unit, health, status_effects) in units {
+            if health.current <= 0. {
+                continue;
+            }
+            let unit_report = self.unit_mut(unit.team, unit.kind);
+            if status_effects.retreating() {
+                unit_report.retreated += 1;
+            } else {
+                unit_report.alive += 1;
+            }
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{} in {:.0} seconds\n", self.outcome.name(), self.duration);
+        for (team, name) in [(Team::Friendly, "Your army"), (Team::Enemy, "Enemy army")] {
+            let team_report = self.team(team);
+            summary += &format!(
+                "\n{}: {} killed, {} retreated, {} standing ({} routed, {} rallied)\n",
+                name,
+                team_report.killed().total_units(),
+                team_report.retreated().total_units(),
+                team_report.alive().total_units(),
+                team_report.routed().total_units(),
+                team_report.rallied().total_units(),
+            );
+            for unit_kind in UnitKind::iter() {
+                let unit_report = &team_report.units[unit_kind];
+                if unit_report.spawned == 0 {
+                    continue;
+                }
+                summary += &format!(
+                    "{}: {} killed, {} retreated, {} standing\n",
+                    unit_kind.name_plural(),
+                    unit_report.killed,
+                    unit_report.retreated,
+                    unit_report.alive,
+                );
+            }
+            summary += &format!(
+                "Damage dealt: {}\nDamage taken: {}\nFriendly fire: {:.0}\n",
+                damage_split(&team_report.damage_dealt_by_kind()),
+                damage_split(&team_report.damage_received_by_kind()),
+                team_report.friendly_fire_damage(),
+            );
+        }
+        summary
+    }
+}
+
+fn damage_split(damage: &EnumMap<DamageKind, f32>) -> String {
+    let total = damage.values().sum::<f32>();
+    let kinds = damage
+        .iter()
+        .filter(|(_, damage)| **damage >= 0.5)
+        .map(|(damage_kind, damage)| format!("{} {:.0}", damage_kind.name(), damage))
+        .collect::<Vec<_>>();
+    if kinds.is_empty() {
+        format!("{:.0}", total)
+    } else {
+        format!("{:.0} ({})", total, kinds.join(", "))
+    }
+}
+
+#[derive(Default, Clone, PartialEq)]
+pub struct TeamReport {
+    pub units: EnumMap<UnitKind, UnitReport>,
+}
+
+impl TeamReport {
+    pub fn spawned(&self) -> UnitComposition {
+        self.composition(|unit_report| unit_report.spawned)
+    }
+
+    pub fn killed(&self) -> UnitComposition {
+        self.composition(|unit_report| unit_report.killed)
+    }
+
+    pub fn retreated(&self) -> UnitComposition {
+        self.composition(|unit_report| unit_report.retreated)
+    }
+
+    pub fn alive(&self) -> UnitComposition {
+        self.composition(|unit_report| unit_report.alive)
+    }
+
+    pub fn routed(&self) -> UnitComposition {
+        self.composition(|unit_report| unit_report.routed)
+    }
+
+    pub fn rallied(&self) -> UnitComposition {
+        self.composition(|unit_report| unit_report.rallied)
+    }
+
+    pub fn damage_dealt(&self) -> f32 {
+        self.units
+            .values()
+            .map(|unit_report| unit_report.damage_dealt.values().sum::<f32>())
+            .sum()
+    }
+
+    pub fn damage_received(&self) -> f32 {
+        self.units
+            .values()
+            .map(|unit_report| unit_report.damage_received.values().sum::<f32>())
+            .sum()
+    }
+
+    pub fn damage_dealt_by_kind(&self) -> EnumMap<DamageKind, f32> {
+        self.damage_by_kind(|unit_report| &unit_report.damage_dealt)
+    }
+
+    pub fn damage_received_by_kind(&self) -> EnumMap<DamageKind, f32> {
+        self.damage_by_kind(|unit_report| &unit_report.damage_received)
+    }
+
+    pub fn friendly_fire_damage(&self) -> f32 {
+        self.units
+            .values()
+            .map(|unit_report| unit_report.friendly_fire_damage)
+            .sum()
+    }
+
+    fn damage_by_kind(
+        &self,
+        f: impl Fn(&UnitReport) -> &EnumMap<DamageKind, f32>,
+    ) -> EnumMap<DamageKind, f32> {
+        let mut damage = EnumMap::default();
+        for unit_report in self.units.values() {
+            for (damage_kind, amount) in f(unit_report).iter() {
+                damage[damage_kind] += amount;
+            }
+        }
+        damage
+    }
+
+    fn composition(&self, f: impl Fn(&UnitReport) -> usize) -> UnitComposition {
+        let mut unit_composition = UnitComposition::empty();
+        for unit_kind in UnitKind::iter() {
+            unit_composition.set_count(unit_kind, f(&self.units[unit_kind]));
+        }
+        unit_composition
+    }
+}
+
+#[derive(Default, Clone, PartialEq)]
+pub struct UnitReport {
+    pub spawned: usize,
+    pub killed: usize,
+    pub retreated: usize,
+    pub alive: usize,
+    pub routed: usize,
+    pub rallied: usize,
+    pub damage_dealt: EnumMap<DamageKind, f32>,
+    pub damage_received: EnumMap<DamageKind, f32>,
+    pub friendly_fire_damage: f32,
+    pub combustion_kills: usize,
+    pub explosion_kills: usize,
 }
 
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct BattleConfig {
     pub friendly_units: UnitComposition,
     pub friendly_modifiers: BattleModifiers,
+    // spices fed to a single unit kind, on top of the whole army's modifiers
+    #[serde(default)]
+    pub friendly_unit_modifiers: EnumMap<UnitKind, BattleModifiers>,
     pub friendly_banner: Banner,
+    #[serde(default)]
+    pub friendly_formation: Option<Formation>,
     pub enemy_units: UnitComposition,
     pub enemy_modifiers: BattleModifiers,
     pub enemy_banner: Banner,
+    #[serde(default)]
+    pub enemy_formation: Option<Formation>,
+    #[serde(default)]
+    pub victory_conditions: Vec<VictoryCondition>,
+    #[serde(default)]
+    pub intervention_budget: usize,
+    #[serde(default)]
+    pub seed: u64,
 }
 
 impl BattleConfig {
@@ -147,6 +384,13 @@ impl BattleConfig {
             Team::Enemy => &self.enemy_units,
         }
     }
+
+    pub fn get_formation(&self, team: Team) -> Option<&Formation> {
+        match team {
+            Team::Friendly => self.friendly_formation.as_ref(),
+            Team::Enemy => self.enemy_formation.as_ref(),
+        }
+    }
 }
 
 #[derive(Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
@@ -226,7 +470,50 @@ impl UnitComposition {
     }
 }
 
-pub type BattleModifiers = EnumMap<BattleModifier, bool>;
+// also lives on each unit, so effects can be applied to some units mid-battle
+#[derive(Default, Clone, Copy, Component, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BattleModifiers(EnumMap<BattleModifier, usize>);
+
+impl BattleModifiers {
+    pub fn get_stacks(&self, modifier: BattleModifier) -> usize {
+        self.0[modifier]
+    }
+
+    pub fn set_stacks(&mut self, modifier: BattleModifier, stacks: usize) {
+        self.0[modifier] = stacks;
+    }
+
+    pub fn add_stack(&mut self, modifier: BattleModifier) {
+        self.0[modifier] += 1;
+    }
+
+    pub fn add_stacks(&mut self, other: &BattleModifiers) {
+        for (modifier, stacks) in other.0.iter() {
+            self.0[modifier] += stacks;
+        }
+    }
+
+    pub fn active(&self, modifier: BattleModifier) -> bool {
+        self.0[modifier] > 0
+    }
+
+    pub fn magnitude(&self, modifier: BattleModifier) -> f32 {
+        modifier.magnitude(self.0[modifier])
+    }
+
+    pub fn attack_speed(&self) -> f32 {
+        self.magnitude(BattleModifier::QuickAttack) * self.magnitude(BattleModifier::Slowness)
+    }
+
+    pub fn defense_modifiers(&self) -> DefenseModifiers {
+        enum_map! {
+            DefenseModifier::Fire => self.active(BattleModifier::Fire),
+            DefenseModifier::Ice => self.active(BattleModifier::Ice),
+            DefenseModifier::Wet => self.active(BattleModifier::Wet),
+        }
+    }
+}
 
 #[derive(Clone, Copy, PartialEq, Eq, Enum, EnumIter, Serialize, Deserialize)]
 pub enum BattleModifier {
@@ -265,6 +552,51 @@ impl BattleModifier {
             Self::Slowness => "Slowness",
         }
     }
+
+    // first stack gives the full effect, further stacks add less
+    pub fn magnitude(&self, stacks: usize) -> f32 {
+        if stacks == 0 {
+            return match self {
+                Self::ExtraDefense
+                | Self::ExtraAttack
+                | Self::QuickAttack
+                | Self::ExtraSpeed
+                | Self::Slowness
+                | Self::Cowardly => 1.,
+                _ => 0.,
+            };
+        }
+        let extra_stacks = (stacks - 1) as f32;
+        match self {
+            Self::ExtraDefense => 4. + extra_stacks * 2.,
+            Self::ExtraAttack => 1.5 + extra_stacks * 0.25,
+            Self::QuickAttack => 2. + extra_stacks * 0.5,
+            Self::ExtraSpeed => 2. + extra_stacks * 0.5,
+            Self::Slowness => 0.5_f32.powi(stacks as i32),
+            Self::FriendlyFire => 1. - 0.75_f32.powi(stacks as i32),
+            Self::Blindness => 1. - 0.5_f32.powi(stacks as i32),
+            Self::Cowardly => 0.6_f32.powi(stacks as i32),
+            Self::Explosive | Self::Combustion => stacks as f32,
+            Self::Fire | Self::Ice | Self::Wet | Self::Sickness => 1.,
+        }
+    }
+
+    pub fn describe(&self, stacks: usize) -> String {
+        let magnitude = self.magnitude(stacks);
+        match self {
+            Self::ExtraDefense => format!("Defense x{}", magnitude),
+            Self::ExtraAttack => format!("Damage x{}", magnitude),
+            Self::QuickAttack => format!("Attack speed x{}", magnitude),
+            Self::ExtraSpeed => format!("Speed x{}", magnitude),
+            Self::Slowness => format!("Speed and attack speed x{}", magnitude),
+            Self::FriendlyFire => format!("{:.0}% friendly fire", magnitude * 100.),
+            Self::Blindness => format!("{:.0}% of units blinded", magnitude * 100.),
+            Self::Cowardly => format!("{:.0}% morale", magnitude * 100.),
+            Self::Explosive => format!("Explosions x{}", magnitude),
+            Self::Combustion => format!("Combustion x{}", magnitude),
+            Self::Fire | Self::Ice | Self::Wet | Self::Sickness => self.name().to_owned(),
+        }
+    }
 }
 
 pub struct BattleStartEvent {
@@ -281,16 +613,21 @@ pub struct BattleEndedEvent {
 fn battle_start(
     mut start_events: EventReader<BattleStartEvent>,
     mut battle_state: ResMut<BattleState>,
+    mut battle_rng: ResMut<BattleRng>,
     mut battlefield_spawn_events: EventWriter<BattlefieldSpawnEvent>,
     mut battle_splash_spawn_events: EventWriter<BattleSplashSpawnEvent>,
     mut unit_spawn_events: EventWriter<UnitSpawnEvent>,
     mut banner_spawn_events: EventWriter<BannerSpawnEvent>,
+    unit_definitions: Res<UnitDefinitions>,
 ) {
     for start_event in start_events.iter() {
         *battle_state = BattleState::default();
+        *battle_rng = BattleRng::new(start_event.config.seed);
         battle_state.battling = true;
         battle_state.friendly_modifiers = start_event.config.friendly_modifiers;
+        battle_state.friendly_unit_modifiers = start_event.config.friendly_unit_modifiers;
         battle_state.enemy_modifiers = start_event.config.enemy_modifiers;
+        battle_state.victory_conditions = start_event.config.victory_conditions.clone();
         battlefield_spawn_events.send_default();
         battle_splash_spawn_events.send(BattleSplashSpawnEvent {
             play_battle_start: !start_event.sandbox,
@@ -299,23 +636,30 @@ fn battle_start(
             battle_state.phase = BattlePhase::Battling;
         }
 
-        const X_DISTANCE: f32 = 400.;
-        const Y_MIN: f32 = -400.;
-        const Y_MAX: f32 = -200.;
-        let mut rng = thread_rng();
         for team in Team::iter() {
             let units = start_event.config.get_units(team);
+            let formation = start_event.config.get_formation(team);
             for unit_kind in UnitKind::iter() {
-                let unit_stats = unit_kind.stats();
-                for _ in 0..units.get_count(unit_kind) {
-                    let x = (rng
-                        .gen_range(unit_stats.spawn_distance_min..unit_stats.spawn_distance_max)
-                        + X_DISTANCE)
-                        * -team.move_direction();
-                    let y = rng.gen_range(Y_MIN..Y_MAX);
+                let unit_stats = unit_definitions.unit_stats(unit_kind);
+                let formation_cells = formation
+                    .map(|formation| formation.cells_for(unit_kind))
+                    .unwrap_or_default();
+                battle_state.report.unit_mut(team, unit_kind).spawned = units.get_count(unit_kind);
+                for unit_index in 0..units.get_count(unit_kind) {
+                    let position = if formation_cells.is_empty() {
+                        let x = (battle_rng.gen_range(
+                            unit_stats.spawn_distance_min..unit_stats.spawn_distance_max,
+                        ) + SPAWN_X_DISTANCE)
+                            * -team.move_direction();
+                        let y = battle_rng.gen_range(SPAWN_Y_MIN..SPAWN_Y_MAX);
+                        Vec2::new(x, y)
+                    } else {
+                        let (row, lane) = formation_cells[unit_index % formation_cells.len()];
+                        Formation::spawn_position(team, row, lane, &mut *battle_rng)
+                    };
                     unit_spawn_events.send(UnitSpawnEvent {
                         kind: unit_kind,
-                        position: Vec2::new(x, y),
+                        position,
                         team,
                     });
                 }
@@ -324,11 +668,11 @@ fn battle_start(
 
         banner_spawn_events.send(BannerSpawnEvent {
             banner: start_event.config.friendly_banner,
-            position: Vec2::new(-950., -270.),
+            position: Team::Friendly.banner_position(),
         });
         banner_spawn_events.send(BannerSpawnEvent {
             banner: start_event.config.enemy_banner,
-            position: Vec2::new(850., -270.),
+            position: Team::Enemy.banner_position(),
         });
     }
 }
@@ -343,11 +687,72 @@ fn battle_unit_die(
     }
     for health_die_event in health_die_events.iter() {
         if let Ok(unit) = unit_query.get(health_die_event.entity) {
-            if unit.team == Team::Friendly {
-                battle_state
-                    .report
-                    .dead_units
-                    .mutate_count(unit.kind, |i| i + 1);
+            battle_state.report.unit_mut(unit.team, unit.kind).killed += 1;
+        }
+    }
+}
+
+fn battle_report_morale(
+    mut morale_events: EventReader<MoraleEvent>,
+    mut battle_state: ResMut<BattleState>,
+    unit_query: Query<&Unit>,
+) {
+    if !battle_state.battling {
+        return;
+    }
+    for morale_event in morale_events.iter() {
+        if let Ok(unit) = unit_query.get(morale_event.entity) {
+            let unit_report = battle_state.report.unit_mut(unit.team, unit.kind);
+            match morale_event.kind {
+                MoraleEventKind::Routed => unit_report.routed += 1,
+                MoraleEventKind::Rallied => unit_report.rallied += 1,
+            }
+        }
+    }
+}
+
+// runs before health is applied so the damage that actually landed can be tallied
+fn battle_report_damage(
+    mut damage_receive_events: EventReader<DamageReceiveEvent>,
+    mut battle_state: ResMut<BattleState>,
+    unit_query: Query<(&Unit, &Health)>,
+) {
+    if !battle_state.battling {
+        return;
+    }
+    let mut remaining_health = HashMap::new();
+    for damage_receive_event in damage_receive_events.iter() {
+        let Ok((unit, health)) = unit_query.get(damage_receive_event.entity) else {
+            continue;
+        };
+        let remaining_health = remaining_health
+            .entry(damage_receive_event.entity)
+            .or_insert(health.current);
+        if *remaining_health <= 0. {
+            continue;
+        }
+        let damage = damage_receive_event.damage.min(*remaining_health);
+        *remaining_health -= damage;
+        let damage_kind = damage_receive_event.damage_kind;
+        let source = damage_receive_event.source;
+        let report = &mut battle_state.report;
+        report.unit_mut(unit.team, unit.kind).damage_received[damage_kind] += damage;
+        if source.team != unit.team {
+            report.unit_mut(source.team, source.kind).damage_dealt[damage_kind] += damage;
+        } else if source.cause == DamageCause::Attack {
+            report
+                .unit_mut(source.team, source.kind)
+                .friendly_fire_damage += damage;
+        }
+        if *remaining_health <= 0. {
+            match source.cause {
+                DamageCause::Attack | DamageCause::Poison => {}
+                DamageCause::Combustion => {
+                    report.unit_mut(source.team, source.kind).combustion_kills += 1;
+                }
+                DamageCause::Explosion => {
+                    report.unit_mut(source.team, source.kind).explosion_kills += 1;
+                }
             }
         }
     }
@@ -357,24 +762,37 @@ fn battle_end_detection(
     mut battle_state: ResMut<BattleState>,
     mut damage_receive_events: EventReader<DamageReceiveEvent>,
     mut battle_splash_play_events: EventWriter<BattleSplashPlayEvent>,
-    unit_query: Query<&Unit>,
+    unit_query: Query<(&Unit, &Health, &StatusEffects, &GlobalTransform)>,
     time: Res<FixedTime>,
 ) {
     if battle_state.phase != BattlePhase::Battling {
         return;
     }
-    let mut friendly_count = 0;
-    let mut enemy_count = 0;
-    let mut enemy_has_brute = false;
-    for unit in unit_query.iter() {
-        if !unit.retreating {
-            if unit.team == Team::Friendly {
-                friendly_count += 1;
-            } else if unit.team == Team::Enemy {
-                if unit.kind == UnitKind::Brute {
-                    enemy_has_brute = true;
-                }
-                enemy_count += 1;
+    let mut context = VictoryContext {
+        duration: battle_state.report.duration,
+        friendly_losses: battle_state
+            .report
+            .team(Team::Friendly)
+            .killed()
+            .total_units(),
+        ..Default::default()
+    };
+    for (unit, health, status_effects, unit_transform) in unit_query.iter() {
+        if health.current <= 0. {
+            continue;
+        }
+        if unit.team == Team::Enemy {
+            context.enemies_alive[unit.kind] += 1;
+        }
+        if !status_effects.retreating() {
+            context.standing[unit.team] += 1;
+            let opposite_team = unit.team.opposite_team();
+            if opposite_team
+                .banner_position()
+                .distance(unit_transform.translation().truncate())
+                < BANNER_CAPTURE_DISTANCE
+            {
+                context.banner_reached[opposite_team] = true;
             }
         }
     }
@@ -383,20 +801,27 @@ fn battle_end_detection(
         battle_state.time_since_last_damage = 0.;
     }
     battle_state.time_since_last_damage += time.period.as_secs_f32();
-    if friendly_count == 0 || enemy_count == 0 {
+    battle_state.report.duration += time.period.as_secs_f32();
+    battle_state.report.ticks += 1;
+    if VictoryCondition::evaluate(&battle_state.victory_conditions, &context).is_some() {
         battle_state.end_timer += time.period.as_secs_f32();
     } else {
         battle_state.end_timer = 0.;
     }
-    if battle_state.end_timer > 2.
-        || (battle_state.damage_inflicted && battle_state.time_since_last_damage > 4.)
-        || (!battle_state.damage_inflicted && battle_state.time_since_last_damage > 8.)
-    {
-        let victory = !enemy_has_brute && friendly_count > enemy_count;
-        battle_state.report.victory = victory;
-        battle_state.phase = BattlePhase::End { victory };
+    context.timed_out = (battle_state.damage_inflicted && battle_state.time_since_last_damage > 4.)
+        || (!battle_state.damage_inflicted && battle_state.time_since_last_damage > 8.);
+    if battle_state.end_timer > 2. || context.timed_out {
+        let outcome = VictoryCondition::evaluate(&battle_state.victory_conditions, &context)
+            .unwrap_or(BattleOutcome::Timeout);
+        battle_state.report.outcome = outcome;
+        battle_state.report.count_survivors(
+            unit_query
+                .iter()
+                .map(|(unit, health, status_effects, _)| (unit, health, status_effects)),
+        );
+        battle_state.phase = BattlePhase::End { outcome };
         battle_splash_play_events.send(BattleSplashPlayEvent {
-            kind: if victory {
+            kind: if outcome.victory() {
                 BattleSplashKind::Victory
             } else {
                 BattleSplashKind::Defeat
@@ -416,8 +841,10 @@ pub fn battle_splash_ended(
             sfx.play(SfxKind::Mayhem);
             battle_state.phase = BattlePhase::Battling;
         } else if matches!(battle_state.phase, BattlePhase::End { .. }) {
+            let report = take(&mut battle_state.report);
+            battle_state.last_report = Some(report.clone());
             battle_ended_events.send(BattleEndedEvent {
-                report: take(&mut battle_state.report),
+                report,
                 _private: (),
             });
             battle_state.battling = false;
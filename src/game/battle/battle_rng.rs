@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use rand::{prelude::*, rngs::StdRng};
+
+pub struct BattleRngPlugin;
+
+impl Plugin for BattleRngPlugin {
+    fn build(&self, app: &mut App) {
+        // systems sharing the rng must consume it in the same order every tick, which
+        // BattleSimulationPlugin guarantees by chaining every system that draws from it
+        app.init_resource::<BattleRng>();
+    }
+}
+
+#[derive(Resource)]
+pub struct BattleRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl Default for BattleRng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl BattleRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn random_seed() -> u64 {
+        thread_rng().gen()
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl RngCore for BattleRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
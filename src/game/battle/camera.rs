@@ -0,0 +1,298 @@
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+use bevy_egui::EguiContexts;
+
+use crate::{
+    BattlePhase, BattleState, CameraView, Cursor, Health, SecondOrder, StatusEffects, Team, Unit,
+    DESIRED_HEIGHT, DESIRED_WIDTH,
+};
+
+const CAMERA_ZOOM_MIN: f32 = 0.5;
+const CAMERA_ZOOM_MAX: f32 = 2.5;
+const CAMERA_PAN_SPEED: f32 = 1200.;
+const CAMERA_ZOOM_SPEED: f32 = 1.5;
+const CAMERA_MARGIN: f32 = 400.;
+const FRONT_LINE_DEPTH: f32 = 300.;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum BattleCameraSystem {
+    Update,
+}
+
+pub struct BattleCameraPlugin;
+
+impl Plugin for BattleCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BattleCamera>()
+            .add_system(battle_camera_update.in_set(BattleCameraSystem::Update));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BattleCameraMode {
+    Follow,
+    Fit,
+    Manual,
+}
+
+#[derive(Resource)]
+pub struct BattleCamera {
+    mode: BattleCameraMode,
+    phase: Option<BattlePhase>,
+    position: SecondOrder<Vec2>,
+    zoom: SecondOrder<f32>,
+    manual_position: Vec2,
+    manual_zoom: f32,
+    drag_anchor: Option<Vec2>,
+}
+
+impl Default for BattleCamera {
+    fn default() -> Self {
+        Self {
+            mode: BattleCameraMode::Follow,
+            phase: None,
+            position: SecondOrder::new_frequency_response(Vec2::ZERO, 1., 1., 0.),
+            zoom: SecondOrder::new_frequency_response(1., 1., 1., 0.),
+            manual_position: Vec2::ZERO,
+            manual_zoom: 1.,
+            drag_anchor: None,
+        }
+    }
+}
+
+impl BattleCamera {
+    pub fn mode(&self) -> BattleCameraMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: BattleCameraMode) {
+        if mode == BattleCameraMode::Manual && self.mode != BattleCameraMode::Manual {
+            self.manual_position = self.position.output;
+            self.manual_zoom = self.zoom.output;
+        }
+        self.mode = mode;
+    }
+
+    fn reset(&mut self, phase: Option<BattlePhase>) {
+        *self = Self {
+            phase,
+            ..Default::default()
+        };
+    }
+
+    fn snap(&mut self, position: Vec2, zoom: f32) {
+        self.position = SecondOrder::new_frequency_response(position, 1., 1., 0.);
+        self.zoom = SecondOrder::new_frequency_response(zoom, 1., 1., 0.);
+    }
+}
+
+fn battle_camera_update(
+    mut battle_camera: ResMut<BattleCamera>,
+    mut camera_view: ResMut<CameraView>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut contexts: EguiContexts,
+    unit_query: Query<(&Unit, &Health, &StatusEffects, &GlobalTransform)>,
+    battle_state: Res<BattleState>,
+    keys: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    cursor: Res<Cursor>,
+    time: Res<Time>,
+) {
+    // the camera keeps moving while the battle is paused or slowed down
+    let delta_seconds = time.raw_delta_seconds().min(0.1);
+    let phase = if battle_state.battling() {
+        Some(battle_state.phase())
+    } else {
+        None
+    };
+    if battle_camera.phase != phase {
+        battle_camera.reset(phase);
+    }
+    if phase != Some(BattlePhase::Battling) {
+        mouse_wheel_events.clear();
+        *camera_view = CameraView::default();
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::F) {
+        let mode = if battle_camera.mode() == BattleCameraMode::Fit {
+            BattleCameraMode::Follow
+        } else {
+            BattleCameraMode::Fit
+        };
+        battle_camera.set_mode(mode);
+    }
+    if keys.just_pressed(KeyCode::C) {
+        battle_camera.set_mode(BattleCameraMode::Follow);
+    }
+
+    let mut pan = Vec2::ZERO;
+    if keys.pressed(KeyCode::W) || keys.pressed(KeyCode::Up) {
+        pan.y += 1.;
+    }
+    if keys.pressed(KeyCode::S) || keys.pressed(KeyCode::Down) {
+        pan.y -= 1.;
+    }
+    if keys.pressed(KeyCode::A) || keys.pressed(KeyCode::Left) {
+        pan.x -= 1.;
+    }
+    if keys.pressed(KeyCode::D) || keys.pressed(KeyCode::Right) {
+        pan.x += 1.;
+    }
+    let mut zoom = 0.;
+    if keys.pressed(KeyCode::Q) {
+        zoom += CAMERA_ZOOM_SPEED * delta_seconds;
+    }
+    if keys.pressed(KeyCode::E) {
+        zoom -= CAMERA_ZOOM_SPEED * delta_seconds;
+    }
+    let wants_pointer_input = contexts.ctx_mut().wants_pointer_input();
+    for mouse_wheel_event in mouse_wheel_events.iter() {
+        if wants_pointer_input {
+            continue;
+        }
+        zoom -= match mouse_wheel_event.unit {
+            MouseScrollUnit::Line => mouse_wheel_event.y * 0.1,
+            MouseScrollUnit::Pixel => mouse_wheel_event.y * 0.001,
+        };
+    }
+    let dragging = (mouse_buttons.pressed(MouseButton::Right)
+        || mouse_buttons.pressed(MouseButton::Middle))
+        && !wants_pointer_input;
+    if pan != Vec2::ZERO || zoom != 0. || dragging {
+        battle_camera.set_mode(BattleCameraMode::Manual);
+    }
+
+    let (target_position, target_zoom) = match battle_camera.mode() {
+        BattleCameraMode::Follow => follow_view(&unit_query),
+        BattleCameraMode::Fit => fit_view(&unit_query),
+        BattleCameraMode::Manual => {
+            let current_zoom = battle_camera.manual_zoom;
+            let new_zoom = (current_zoom * (1. + zoom)).clamp(CAMERA_ZOOM_MIN, CAMERA_ZOOM_MAX);
+            // zoom around the cursor so the point under it stays put
+            let mut position = battle_camera.manual_position;
+            position += (cursor.position - position) * (1. - new_zoom / current_zoom);
+            position += pan.normalize_or_zero() * CAMERA_PAN_SPEED * new_zoom * delta_seconds;
+            if dragging {
+                if let Some(drag_anchor) = battle_camera.drag_anchor {
+                    position += drag_anchor - cursor.position;
+                } else {
+                    battle_camera.drag_anchor = Some(cursor.position);
+                }
+            } else {
+                battle_camera.drag_anchor = None;
+            }
+            (position, new_zoom)
+        }
+    };
+    let target_position = target_position.clamp(
+        Vec2::new(DESIRED_WIDTH, DESIRED_HEIGHT) * -0.5,
+        Vec2::new(DESIRED_WIDTH, DESIRED_HEIGHT) * 0.5,
+    );
+    let target_zoom = target_zoom.clamp(CAMERA_ZOOM_MIN, CAMERA_ZOOM_MAX);
+
+    if battle_camera.mode() == BattleCameraMode::Manual {
+        battle_camera.manual_position = target_position;
+        battle_camera.manual_zoom = target_zoom;
+        // manual movement is already smooth, lagging behind it would make dragging drift
+        battle_camera.snap(target_position, target_zoom);
+    } else {
+        battle_camera
+            .position
+            .update(target_position, delta_seconds);
+        battle_camera.zoom.update(target_zoom, delta_seconds);
+    }
+    camera_view.position = battle_camera.position.output;
+    camera_view.zoom = battle_camera.zoom.output;
+}
+
+fn standing_units(
+    unit_query: &Query<(&Unit, &Health, &StatusEffects, &GlobalTransform)>,
+) -> Vec<(Team, Vec2)> {
+    unit_query
+        .iter()
+        .filter(|(_, health, status_effects, _)| {
+            health.current > 0. && !status_effects.retreating()
+        })
+        .map(|(unit, _, _, transform)| (unit.team, transform.translation().truncate()))
+        .collect()
+}
+
+fn view_around(min: Vec2, max: Vec2) -> (Vec2, f32) {
+    let size = max - min + Vec2::splat(CAMERA_MARGIN * 2.);
+    let zoom = (size.x / DESIRED_WIDTH).max(size.y / DESIRED_HEIGHT);
+    ((min + max) * 0.5, zoom)
+}
+
+fn fit_view(unit_query: &Query<(&Unit, &Health, &StatusEffects, &GlobalTransform)>) -> (Vec2, f32) {
+    let positions = unit_query
+        .iter()
+        .filter(|(_, health, _, _)| health.current > 0.)
+        .map(|(_, _, _, transform)| transform.translation().truncate())
+        .collect::<Vec<_>>();
+    if positions.is_empty() {
+        return (Vec2::ZERO, 1.);
+    }
+    let min = positions
+        .iter()
+        .fold(Vec2::splat(f32::MAX), |min, position| min.min(*position));
+    let max = positions
+        .iter()
+        .fold(Vec2::splat(f32::MIN), |max, position| max.max(*position));
+    view_around(min, max)
+}
+
+// centres between each side's front line, the units closest to the enemy
+fn follow_view(
+    unit_query: &Query<(&Unit, &Health, &StatusEffects, &GlobalTransform)>,
+) -> (Vec2, f32) {
+    let units = standing_units(unit_query);
+    let mut front_line_units = vec![];
+    let mut front_line_centres = vec![];
+    for team in [Team::Friendly, Team::Enemy] {
+        let direction = match team {
+            Team::Friendly => 1.,
+            Team::Enemy => -1.,
+        };
+        let team_positions = units
+            .iter()
+            .filter(|(unit_team, _)| *unit_team == team)
+            .map(|(_, position)| *position)
+            .collect::<Vec<_>>();
+        let Some(front) = team_positions
+            .iter()
+            .map(|position| position.x * direction)
+            .reduce(f32::max)
+        else {
+            continue;
+        };
+        let front_line = team_positions
+            .into_iter()
+            .filter(|position| front - position.x * direction < FRONT_LINE_DEPTH)
+            .collect::<Vec<_>>();
+        front_line_centres.push(
+            front_line
+                .iter()
+                .fold(Vec2::ZERO, |sum, position| sum + *position)
+                / front_line.len() as f32,
+        );
+        front_line_units.extend(front_line);
+    }
+    if front_line_centres.is_empty() {
+        return (Vec2::ZERO, 1.);
+    }
+    let min = front_line_units
+        .iter()
+        .fold(Vec2::splat(f32::MAX), |min, position| min.min(*position));
+    let max = front_line_units
+        .iter()
+        .fold(Vec2::splat(f32::MIN), |max, position| max.max(*position));
+    let (_, zoom) = view_around(min, max);
+    let centre = front_line_centres
+        .iter()
+        .fold(Vec2::ZERO, |sum, position| sum + *position)
+        / front_line_centres.len() as f32;
+    (centre, zoom)
+}
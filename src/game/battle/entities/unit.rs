@@ -4,23 +4,36 @@ use bevy_spine::prelude::*;
 use bitflags::bitflags;
 use enum_map::{enum_map, Enum, EnumMap};
 use rand::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 use crate::{
-    AddFixedEvent, AreaOfEffectTargeting, AssetLibrary, BattleModifier, BattlePhase, BattleState,
-    CollisionShape, DamageFlags, DamageInflictEvent, DamageKind, DamageModifier, DamageModifiers,
-    DamageReceiveEvent, DamageSystem, DefenseKind, DefenseModifier, DefenseModifiers, Depth,
-    DepthLayer, EventSet, Feeler, FramesToLive, Health, HealthDieEvent, HitBox, HurtBox,
-    HurtBoxDespawner, Projectile, SpawnSet, SpineAttack, SpineFx, SpineSpawnSet, Target, Team,
-    TempSfxBundle, TextureAtlasFx, Transform2, UpdateSet, YOrder, DEPTH_BLOOD_FX, DEPTH_DRIP_FX,
-    DEPTH_EXPLOSION_FX, DEPTH_ICE_FX, DEPTH_PROJECTILE,
+    area_of_effect_target, ballistic_velocity, expected_hits, AddFixedEvent, AreaOfEffectTarget,
+    AssetLibrary, AttackTimeline, BattleModifier, BattleModifiers, BattlePhase, BattleRng,
+    BattleState, Broadphase, CollisionShape, Crowd, DamageCause, DamageFlags, DamageInflictEvent,
+    DamageKind, DamageModifier, DamageModifiers, DamageReceiveEvent, DamageSource, DamageSystem,
+    DefenseKind, Depth, DepthLayer, EventSet, Feeler, FramesToLive, Health, HealthDieEvent, HitBox,
+    HurtBox, HurtBoxDespawner, HurtBoxTimeline, Morale, Projectile, SpawnSet, SpineFx,
+    SpineSpawnSet, StatusEffect, StatusEffectKind, StatusEffects, Target, TargetPriority, Team,
+    TempSfxBundle, TextureAtlasFx, Transform2, UnitDefinitions, UnitTarget, UpdateSet, YOrder,
+    DEPTH_BLOOD_FX, DEPTH_DRIP_FX, DEPTH_EXPLOSION_FX, DEPTH_ICE_FX, DEPTH_PROJECTILE,
+    PROJECTILE_GRAVITY, TARGET_RETARGET_INTERVAL,
 };
 
 const UNIT_SCALE: f32 = 0.7;
 const UNIT_TRACK_WALK: usize = 0;
 const UNIT_TRACK_ATTACK: usize = 1;
 const UNIT_TRACK_COLOR: usize = 2;
+const UNIT_TURN_AROUND_DISTANCE: f32 = 100.;
+const UNIT_LANE_CHANGE_SPEED: f32 = 0.5;
+const UNIT_CROWD_RADIUS: f32 = 0.25;
+const PROJECTILE_SPEED: f32 = 2500.;
+const PROJECTILE_LIFT: f32 = 300.;
+const PROJECTILE_HEIGHT: f32 = 90.;
+const VOLLEY_DURATION: f32 = 0.5;
+const BLINDNESS_SPREAD: f32 = 3.;
+const EXPLOSION_KNOCKBACK: f32 = 1200.;
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
 pub enum UnitSystem {
@@ -31,10 +44,10 @@ pub enum UnitSystem {
     Update,
     Attack,
     Die,
-    Cowardly,
     UpdateSpriteDirection,
     UpdateAnimations,
     UpdateFeeler,
+    UpdateFire,
     Combust,
     Explode,
     Drip,
@@ -53,11 +66,6 @@ impl Plugin for UnitPlugin {
                     .in_set(SpineSpawnSet)
                     .after(EventSet::<UnitSpawnEvent>::Sender),
             )
-            .add_system(
-                unit_spine_ready
-                    .in_set(UnitSystem::SpineReady)
-                    .in_set(SpineSet::OnReady),
-            )
             .add_system(
                 unit_slow
                     .in_schedule(CoreSchedule::FixedUpdate)
@@ -66,14 +74,6 @@ impl Plugin for UnitPlugin {
                     .before(UnitSystem::Update)
                     .before(EventSet::<DamageReceiveEvent>::Sender),
             )
-            .add_system(
-                unit_damage_fx
-                    .in_schedule(CoreSchedule::FixedUpdate)
-                    .in_set(UnitSystem::DamageFx)
-                    .in_set(UpdateSet)
-                    .in_set(SpineSpawnSet)
-                    .before(EventSet::<DamageReceiveEvent>::Sender),
-            )
             .add_system(
                 unit_update
                     .in_schedule(CoreSchedule::FixedUpdate)
@@ -96,14 +96,6 @@ impl Plugin for UnitPlugin {
                     .in_set(UpdateSet)
                     .after(EventSet::<HealthDieEvent>::Sender),
             )
-            .add_system(
-                unit_cowardly
-                    .in_schedule(CoreSchedule::FixedUpdate)
-                    .in_set(UnitSystem::Cowardly)
-                    .in_set(UpdateSet)
-                    .after(EventSet::<DamageReceiveEvent>::Sender)
-                    .before(UnitSystem::Update),
-            )
             .add_system(
                 unit_update_sprite_direction
                     .in_schedule(CoreSchedule::FixedUpdate)
@@ -111,13 +103,6 @@ impl Plugin for UnitPlugin {
                     .in_set(UpdateSet)
                     .before(UnitSystem::Update),
             )
-            .add_system(
-                unit_update_animations
-                    .in_schedule(CoreSchedule::FixedUpdate)
-                    .in_set(UnitSystem::UpdateAnimations)
-                    .in_set(UpdateSet)
-                    .before(UnitSystem::Update),
-            )
             .add_system(
                 unit_update_feeler
                     .in_schedule(CoreSchedule::FixedUpdate)
@@ -141,17 +126,49 @@ impl Plugin for UnitPlugin {
                     .in_set(EventSet::<DamageInflictEvent>::Sender)
                     .before(UnitSystem::Update),
             )
+            .add_system(
+                unit_spine_ready
+                    .in_set(UnitSystem::SpineReady)
+                    .in_set(SpineSet::OnReady)
+                    .run_if(resource_exists::<AssetLibrary>()),
+            )
+            .add_system(
+                unit_damage_fx
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(UnitSystem::DamageFx)
+                    .in_set(UpdateSet)
+                    .in_set(SpineSpawnSet)
+                    .before(EventSet::<DamageReceiveEvent>::Sender)
+                    .run_if(resource_exists::<AssetLibrary>()),
+            )
+            .add_system(
+                unit_update_animations
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(UnitSystem::UpdateAnimations)
+                    .in_set(UpdateSet)
+                    .after(UnitSystem::Attack)
+                    .run_if(resource_exists::<AssetLibrary>()),
+            )
+            .add_system(
+                unit_update_fire
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(UnitSystem::UpdateFire)
+                    .in_set(UpdateSet)
+                    .after(UnitSystem::Combust)
+                    .run_if(resource_exists::<AssetLibrary>()),
+            )
             .add_system(
                 unit_drip
                     .in_schedule(CoreSchedule::FixedUpdate)
                     .in_set(UnitSystem::Drip)
                     .in_set(UpdateSet)
-                    .before(UnitSystem::Update),
+                    .before(UnitSystem::Update)
+                    .run_if(resource_exists::<AssetLibrary>()),
             );
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Enum, EnumIter)]
+#[derive(Clone, Copy, PartialEq, Eq, Enum, EnumIter, Serialize, Deserialize)]
 pub enum UnitKind {
     Peasant,
     Warrior,
@@ -161,86 +178,6 @@ pub enum UnitKind {
 }
 
 impl UnitKind {
-    pub fn stats(&self) -> UnitStats {
-        match self {
-            UnitKind::Peasant => UnitStats {
-                cost: 1,
-                speed: 300.,
-                speed_slow: 150.,
-                health: 15.,
-                attack: Attack::Claw,
-                defense_kind: DefenseKind::Flesh,
-                spawn_distance_min: 0.,
-                spawn_distance_max: 900.,
-                hit_box_size: Vec2::new(100., 400.),
-                feeler_size: Vec2::new(200., 400.),
-                stop_on_feeler: false,
-                retreat_chance: 0.01,
-                attributes: Attributes::empty(),
-            },
-            UnitKind::Warrior => UnitStats {
-                cost: 5,
-                speed: 200.,
-                speed_slow: 70.,
-                health: 30.,
-                attack: Attack::Sword,
-                defense_kind: DefenseKind::Armor,
-                spawn_distance_min: 0.,
-                spawn_distance_max: 500.,
-                hit_box_size: Vec2::new(300., 400.),
-                feeler_size: Vec2::new(150., 400.),
-                stop_on_feeler: true,
-                retreat_chance: 0.02,
-                attributes: Attributes::empty(),
-            },
-            UnitKind::Archer => UnitStats {
-                cost: 2,
-                speed: 10.,
-                speed_slow: 10.,
-                health: 5.,
-                attack: Attack::Arrow,
-                defense_kind: DefenseKind::Flesh,
-                spawn_distance_min: 400.,
-                spawn_distance_max: 600.,
-                hit_box_size: Vec2::new(100., 400.),
-                feeler_size: Vec2::new(2200., 400.),
-                stop_on_feeler: false,
-                retreat_chance: 0.33,
-                attributes: Attributes::MAY_RETREAT,
-            },
-            UnitKind::Mage => UnitStats {
-                cost: 10,
-                speed: 0.,
-                speed_slow: 0.,
-                health: 10.,
-                attack: Attack::Magic,
-                defense_kind: DefenseKind::Flesh,
-                spawn_distance_min: 600.,
-                spawn_distance_max: 800.,
-                hit_box_size: Vec2::new(100., 400.),
-                feeler_size: Vec2::new(1400., 400.),
-                retreat_chance: 1.,
-                stop_on_feeler: false,
-                attributes: Attributes::MAY_RETREAT,
-            },
-            UnitKind::Brute => UnitStats {
-                cost: 15,
-                speed: 80.,
-                speed_slow: 50.,
-                health: 300.,
-                attack: Attack::Axe,
-                defense_kind: DefenseKind::Armor,
-                spawn_distance_min: 150.,
-                spawn_distance_max: 250.,
-                hit_box_size: Vec2::new(300., 500.),
-                feeler_size: Vec2::new(200., 400.),
-                stop_on_feeler: true,
-                retreat_chance: 0.002,
-                attributes: Attributes::MAY_FRIENDLY_FIRE,
-            },
-        }
-    }
-
     pub fn name(&self) -> &'static str {
         match self {
             UnitKind::Peasant => "Mobling",
@@ -298,24 +235,40 @@ impl UnitKind {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Deserialize)]
 pub struct UnitStats {
     pub cost: usize,
     pub speed: f32,
     pub speed_slow: f32,
     pub health: f32,
     pub attack: Attack,
+    pub attack_timeline: AttackTimeline,
     pub defense_kind: DefenseKind,
     pub spawn_distance_min: f32,
     pub spawn_distance_max: f32,
     pub hit_box_size: Vec2,
     pub feeler_size: Vec2,
     pub stop_on_feeler: bool,
-    pub retreat_chance: f32,
+    pub morale: f32,
+    pub mass: f32,
     pub attributes: Attributes,
+    #[serde(default)]
+    pub target_priority: TargetPriority,
+    #[serde(default)]
+    pub accuracy_spread: f32,
+}
+
+impl UnitStats {
+    pub fn with_modifiers(mut self, modifiers: &BattleModifiers) -> Self {
+        let extra_speed = modifiers.magnitude(BattleModifier::ExtraSpeed);
+        let slowness = modifiers.magnitude(BattleModifier::Slowness);
+        self.speed *= extra_speed * slowness;
+        self.speed_slow *= (1. + (extra_speed - 1.) * 0.5) * slowness;
+        self
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Enum, Deserialize)]
 pub enum Attack {
     Claw,
     Sword,
@@ -324,66 +277,28 @@ pub enum Attack {
     Axe,
 }
 
-impl Attack {
-    pub fn stats(&self) -> AttackStats {
-        match self {
-            Attack::Claw => AttackStats {
-                damage: 0.5,
-                damage_kind: DamageKind::Flesh,
-                hit_count: 3,
-                hurt_box_kind: AttackHurtBoxKind::OffsetRect {
-                    offset: 100.,
-                    size: Vec2::new(200., 300.),
-                },
-            },
-            Attack::Sword => AttackStats {
-                damage: 5.,
-                damage_kind: DamageKind::Sword,
-                hit_count: 5,
-                hurt_box_kind: AttackHurtBoxKind::OffsetRect {
-                    offset: 150.,
-                    size: Vec2::new(150., 150.),
-                },
-            },
-            Attack::Arrow => AttackStats {
-                damage: 2.,
-                damage_kind: DamageKind::Arrow,
-                hit_count: 1,
-                hurt_box_kind: AttackHurtBoxKind::Projectile,
-            },
-            Attack::Magic => AttackStats {
-                damage: 1.,
-                damage_kind: DamageKind::Magic,
-                hit_count: 20,
-                hurt_box_kind: AttackHurtBoxKind::AreaOfEffect {
-                    size: Vec2::new(400., 400.),
-                },
-            },
-            Attack::Axe => AttackStats {
-                damage: 15.,
-                damage_kind: DamageKind::Sword,
-                hit_count: 3,
-                hurt_box_kind: AttackHurtBoxKind::OffsetRect {
-                    offset: 180.,
-                    size: Vec2::new(300., 500.),
-                },
-            },
-        }
-    }
-}
-
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Deserialize)]
 pub struct AttackStats {
     pub damage: f32,
     pub damage_kind: DamageKind,
     pub hit_count: usize,
     pub hurt_box_kind: AttackHurtBoxKind,
+    #[serde(default)]
+    pub knockback: f32,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Deserialize)]
 pub enum AttackHurtBoxKind {
-    OffsetRect { offset: f32, size: Vec2 },
-    AreaOfEffect { size: Vec2 },
+    OffsetRect {
+        offset: f32,
+        size: Vec2,
+    },
+    AreaOfEffect {
+        size: Vec2,
+        damage_start: f32,
+        damage_end: f32,
+        duration: f32,
+    },
     Projectile,
 }
 
@@ -392,46 +307,76 @@ pub struct Unit {
     pub team: Team,
     pub kind: UnitKind,
     pub stats: UnitStats,
-    pub damage_slow_timer: f32,
-    pub ice_slow_timer: f32,
-    pub retreating: bool,
-    pub blind: bool,
     pub attributes: Attributes,
+    pub attack_time: Option<f32>,
+    pub target: Option<UnitTarget>,
+    pub retarget_time: f32,
 }
 
 #[derive(Component)]
 pub struct UnitFire;
 
 impl Unit {
-    pub fn can_attack(&self) -> bool {
-        !self.retreating
+    pub fn damage_source(&self, cause: DamageCause) -> DamageSource {
+        DamageSource {
+            team: self.team,
+            kind: self.kind,
+            cause,
+        }
     }
 
-    pub fn move_direction(&self) -> f32 {
+    pub fn move_direction(&self, status_effects: &StatusEffects) -> f32 {
         let mut move_dir = self.team.move_direction();
-        if self.retreating {
+        if status_effects.retreating() {
             move_dir *= -1.;
+        } else if let Some(target) = self.target {
+            // only turn around for targets well behind, so units don't flip over ones right above them
+            if target.offset.x * move_dir < -UNIT_TURN_AROUND_DISTANCE {
+                move_dir *= -1.;
+            }
         }
         move_dir
     }
 
-    pub fn speed(&self) -> f32 {
-        let ice_multiplier = if self.ice_slow_timer > 0. { 0.3 } else { 1. };
-        if self.retreating {
-            300. * ice_multiplier
-        } else if self.damage_slow_timer > 0. {
-            self.stats.speed_slow * ice_multiplier
+    pub fn attack_speed(&self, modifiers: &BattleModifiers) -> f32 {
+        modifiers.attack_speed()
+    }
+
+    pub fn accuracy_spread(&self, status_effects: &StatusEffects) -> f32 {
+        if status_effects.has(StatusEffectKind::Blinded) {
+            self.stats.accuracy_spread * (1. + BLINDNESS_SPREAD)
         } else {
-            self.stats.speed * ice_multiplier
+            self.stats.accuracy_spread
         }
     }
+
+    pub fn speed(&self, status_effects: &StatusEffects) -> f32 {
+        status_effects.speed(self.stats.speed, self.stats.speed_slow)
+    }
 }
 
 bitflags! {
     pub struct Attributes: u32 {
-        const MAY_RETREAT = 0b00000001;
-        const MAY_FRIENDLY_FIRE = 0b00000010;
-        const ON_FIRE = 0b00000100;
+        const MAY_FRIENDLY_FIRE = 0b00000001;
+        const ON_FIRE = 0b00000010;
+    }
+}
+
+impl<'de> Deserialize<'de> for Attributes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        enum Attribute {
+            MayFriendlyFire,
+            OnFire,
+        }
+        let mut attributes = Attributes::empty();
+        for attribute in Vec::<Attribute>::deserialize(deserializer)? {
+            attributes |= match attribute {
+                Attribute::MayFriendlyFire => Attributes::MAY_FRIENDLY_FIRE,
+                Attribute::OnFire => Attributes::ON_FIRE,
+            };
+        }
+        Ok(attributes)
     }
 }
 
@@ -445,122 +390,138 @@ fn unit_spawn(
     mut commands: Commands,
     mut spawn_events: EventReader<UnitSpawnEvent>,
     battle_state: Res<BattleState>,
-    asset_library: Res<AssetLibrary>,
+    asset_library: Option<Res<AssetLibrary>>,
+    unit_definitions: Res<UnitDefinitions>,
+    mut battle_rng: ResMut<BattleRng>,
 ) {
-    let mut rng = thread_rng();
     for spawn_event in spawn_events.iter() {
         let team = spawn_event.team;
-        let team_modifiers = battle_state.get_modifiers(team);
-        let mut defense_modifiers = DefenseModifiers::default();
-        if team_modifiers[BattleModifier::Fire] {
-            defense_modifiers[DefenseModifier::Fire] = true;
-        }
-        if team_modifiers[BattleModifier::Ice] {
-            defense_modifiers[DefenseModifier::Ice] = true;
-        }
-        if team_modifiers[BattleModifier::Wet] {
-            defense_modifiers[DefenseModifier::Wet] = true;
+        let modifiers = battle_state.get_unit_modifiers(team, spawn_event.kind);
+        let stats = unit_definitions
+            .unit_stats(spawn_event.kind)
+            .clone()
+            .with_modifiers(&modifiers);
+        let mut status_effects = StatusEffects::default();
+        let source = DamageSource {
+            team,
+            kind: spawn_event.kind,
+            cause: DamageCause::Attack,
+        };
+        if modifiers.active(BattleModifier::Blindness)
+            && battle_rng.gen_bool(modifiers.magnitude(BattleModifier::Blindness) as f64)
+        {
+            status_effects.apply(StatusEffect::new(StatusEffectKind::Blinded, source));
         }
-        let mut stats = spawn_event.kind.stats();
-        if team_modifiers[BattleModifier::ExtraSpeed] {
-            stats.speed *= 2.;
-            stats.speed_slow *= 1.5;
+        if modifiers.active(BattleModifier::Wet) {
+            status_effects.apply(StatusEffect::new(StatusEffectKind::Soaked, source));
         }
-        if team_modifiers[BattleModifier::Slowness] {
-            stats.speed *= 0.5;
-            stats.speed_slow *= 0.5;
+        if stats.attributes.contains(Attributes::ON_FIRE) {
+            status_effects.apply(StatusEffect::new(
+                StatusEffectKind::Burning,
+                DamageSource {
+                    cause: DamageCause::Combustion,
+                    ..source
+                },
+            ));
         }
-        let mut crossfades = Crossfades::new();
-        crossfades.add("idle", "walk", 0.2);
-        crossfades.add("walk", "idle", 0.2);
-        crossfades.add("attack", "attack_stop", 0.2);
-        crossfades.add("attack_stop", "attack", 0.2);
-        commands
-            .spawn((
-                SpineBundle {
-                    skeleton: spawn_event.kind.skeleton(asset_library.as_ref()),
-                    crossfades,
-                    ..Default::default()
+        let mut unit_entity = commands.spawn((
+            TransformBundle::default(),
+            Transform2::from_translation(spawn_event.position).with_scale(Vec2::new(
+                if spawn_event.team == Team::Friendly {
+                    UNIT_SCALE
+                } else {
+                    -UNIT_SCALE
                 },
-                Transform2::from_translation(spawn_event.position).with_scale(Vec2::new(
-                    if spawn_event.team == Team::Friendly {
-                        UNIT_SCALE
-                    } else {
-                        -UNIT_SCALE
-                    },
-                    UNIT_SCALE,
-                )),
-                Depth::from(DepthLayer::YOrder(0.)),
-                Health::new(stats.health),
-                HitBox {
-                    flags: team.hit_flags(),
-                    shape: CollisionShape::Rect {
-                        offset: Vec2::new(0., stats.hit_box_size.y * 0.25),
-                        size: stats.hit_box_size,
-                    },
-                    defense: if team_modifiers[BattleModifier::ExtraDefense] {
-                        4.
-                    } else {
-                        1.
-                    },
-                    defense_kind: stats.defense_kind,
-                    defense_modifiers,
+                UNIT_SCALE,
+            )),
+            Depth::from(DepthLayer::YOrder(0.)),
+            Health::new(stats.health),
+            HitBox {
+                flags: team.hit_flags(),
+                shape: CollisionShape::Rect {
+                    offset: Vec2::new(0., stats.hit_box_size.y * 0.25),
+                    size: stats.hit_box_size,
                 },
-                YOrder,
-                Target { team },
-                Feeler {
-                    shape: CollisionShape::None,
-                    flags: team.hurt_flags(),
+                defense: modifiers.magnitude(BattleModifier::ExtraDefense),
+                defense_kind: stats.defense_kind,
+                defense_modifiers: modifiers.defense_modifiers(),
+            },
+            YOrder,
+            Target { team },
+            Feeler {
+                shape: CollisionShape::None,
+                flags: team.hurt_flags(),
+                ..Default::default()
+            },
+            Unit {
+                team,
+                kind: spawn_event.kind,
+                stats: stats.clone(),
+                attributes: stats.attributes,
+                attack_time: None,
+                target: None,
+                retarget_time: battle_rng.gen_range(0.0..TARGET_RETARGET_INTERVAL),
+            },
+            status_effects,
+            Morale::new(Morale::cowardly_baseline(
+                stats.morale,
+                modifiers.magnitude(BattleModifier::Cowardly),
+            )),
+            Crowd {
+                mass: stats.mass,
+                radius: stats.hit_box_size.x * UNIT_CROWD_RADIUS,
+                ..Default::default()
+            },
+            modifiers,
+        ));
+        if let Some(asset_library) = asset_library.as_ref() {
+            let mut crossfades = Crossfades::new();
+            crossfades.add("idle", "walk", 0.2);
+            crossfades.add("walk", "idle", 0.2);
+            crossfades.add("attack", "attack_stop", 0.2);
+            crossfades.add("attack_stop", "attack", 0.2);
+            unit_entity
+                .insert(SpineBundle {
+                    skeleton: spawn_event.kind.skeleton(asset_library),
+                    crossfades,
                     ..Default::default()
-                },
-                Unit {
-                    team,
-                    kind: spawn_event.kind,
-                    stats,
-                    damage_slow_timer: 0.,
-                    ice_slow_timer: 0.,
-                    retreating: false,
-                    blind: team_modifiers[BattleModifier::Blindness] && rng.gen_bool(0.5),
-                    attributes: stats.attributes,
-                },
-            ))
-            .with_children(|parent| {
-                parent.spawn((
-                    SpriteSheetBundle {
-                        texture_atlas: asset_library.image_atlas_fire.clone(),
-                        visibility: Visibility::Hidden,
-                        ..Default::default()
-                    },
-                    AudioPlusSource::new(asset_library.sounds.unit_fire.clone()),
-                    Transform2::from_xy(0., 150.),
-                    Depth::Inherit(0.01),
-                    UnitFire,
-                ));
-            });
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        SpriteSheetBundle {
+                            texture_atlas: asset_library.image_atlas_fire.clone(),
+                            visibility: Visibility::Hidden,
+                            ..Default::default()
+                        },
+                        AudioPlusSource::new(asset_library.sounds.unit_fire.clone()),
+                        Transform2::from_xy(0., 150.),
+                        Depth::Inherit(0.01),
+                        UnitFire,
+                    ));
+                });
+        }
     }
 }
 
 fn unit_spine_ready(
     mut spine_ready_events: EventReader<SpineReadyEvent>,
-    mut spine_query: Query<(&mut Spine, &Unit)>,
-    battle_state: Res<BattleState>,
+    mut spine_query: Query<(&mut Spine, &BattleModifiers), With<Unit>>,
 ) {
     for spine_ready_event in spine_ready_events.iter() {
-        if let Ok((mut spine, unit)) = spine_query.get_mut(spine_ready_event.entity) {
-            let modifiers = battle_state.get_modifiers(unit.team);
-            if modifiers[BattleModifier::Fire] {
+        if let Ok((mut spine, modifiers)) = spine_query.get_mut(spine_ready_event.entity) {
+            if modifiers.active(BattleModifier::Fire) {
                 let _ = spine.animation_state.set_animation_by_name(
                     UNIT_TRACK_COLOR as i32,
                     "fire",
                     true,
                 );
-            } else if modifiers[BattleModifier::Wet] {
+            } else if modifiers.active(BattleModifier::Wet) {
                 let _ = spine.animation_state.set_animation_by_name(
                     UNIT_TRACK_COLOR as i32,
                     "wet",
                     true,
                 );
-            } else if modifiers[BattleModifier::Ice] {
+            } else if modifiers.active(BattleModifier::Ice) {
                 let _ = spine.animation_state.set_animation_by_name(
                     UNIT_TRACK_COLOR as i32,
                     "ice",
@@ -572,44 +533,18 @@ fn unit_spine_ready(
 }
 
 fn unit_slow(
-    mut unit_query: Query<(&mut Unit, &GlobalTransform)>,
+    mut unit_query: Query<&mut StatusEffects, With<Unit>>,
     mut damage_receive_events: EventReader<DamageReceiveEvent>,
-    mut commands: Commands,
-    time: Res<FixedTime>,
-    asset_library: Res<AssetLibrary>,
 ) {
-    let mut rng = thread_rng();
-    for (mut unit, _) in unit_query.iter_mut() {
-        unit.damage_slow_timer -= time.period.as_secs_f32();
-        if unit.damage_slow_timer < 0. {
-            unit.damage_slow_timer = 0.;
-        }
-        unit.ice_slow_timer -= time.period.as_secs_f32();
-        if unit.ice_slow_timer < 0. {
-            unit.ice_slow_timer = 0.;
-        }
-    }
     for damage_receive_event in damage_receive_events.iter() {
-        if let Ok((mut unit, unit_transform)) = unit_query.get_mut(damage_receive_event.entity) {
+        if let Ok(mut status_effects) = unit_query.get_mut(damage_receive_event.entity) {
+            let source = damage_receive_event.source;
             if damage_receive_event.slow {
-                unit.ice_slow_timer = 0.5;
-                if rng.gen_bool(0.1) {
-                    commands.spawn((
-                        SpriteSheetBundle {
-                            texture_atlas: asset_library.image_atlas_ice.clone(),
-                            ..Default::default()
-                        },
-                        Transform2::from_translation(
-                            unit_transform.translation().truncate()
-                                + Vec2::new(rng.gen_range(-20.0..20.0), rng.gen_range(0.0..140.0)),
-                        )
-                        .with_scale(Vec2::splat(0.4)),
-                        Depth::from(DEPTH_ICE_FX),
-                        TextureAtlasFx::new(4),
-                    ));
-                }
+                status_effects
+                    .apply(StatusEffect::new(StatusEffectKind::Frozen, source).with_duration(0.5));
             }
-            unit.damage_slow_timer = 0.5;
+            status_effects
+                .apply(StatusEffect::new(StatusEffectKind::Slowed, source).with_duration(0.5));
         }
     }
 }
@@ -617,23 +552,46 @@ fn unit_slow(
 fn unit_damage_fx(
     mut damage_receive_events: EventReader<DamageReceiveEvent>,
     mut commands: Commands,
-    unit_query: Query<(&GlobalTransform, &Unit)>,
+    unit_query: Query<(&GlobalTransform, &Unit, &StatusEffects)>,
     asset_library: Res<AssetLibrary>,
 ) {
     let mut rng = thread_rng();
     for damage_receive_event in damage_receive_events.iter() {
-        if let Ok((unit_transform, unit)) = unit_query.get(damage_receive_event.entity) {
-            if !unit.attributes.contains(Attributes::ON_FIRE) {
-                if rng.gen_bool(0.2) {
+        if let Ok((unit_transform, unit, status_effects)) =
+            unit_query.get(damage_receive_event.entity)
+        {
+            if damage_receive_event.slow && rng.gen_bool(0.1) {
+                commands.spawn((
+                    SpriteSheetBundle {
+                        texture_atlas: asset_library.image_atlas_ice.clone(),
+                        ..Default::default()
+                    },
+                    Transform2::from_translation(
+                        unit_transform.translation().truncate()
+                            + Vec2::new(rng.gen_range(-20.0..20.0), rng.gen_range(0.0..140.0)),
+                    )
+                    .with_scale(Vec2::splat(0.4)),
+                    Depth::from(DEPTH_ICE_FX),
+                    TextureAtlasFx::new(4),
+                ));
+            }
+            if !status_effects.has(StatusEffectKind::Burning) {
+                // projectiles splat where they struck, everything else somewhere on the body
+                if damage_receive_event.contact.is_some() || rng.gen_bool(0.2) {
                     commands.spawn((
                         SpriteSheetBundle {
                             texture_atlas: asset_library.image_atlas_blood_splat.clone(),
                             ..Default::default()
                         },
-                        Transform2::from_translation(
-                            unit_transform.translation().truncate()
-                                + Vec2::new(rng.gen_range(-20.0..20.0), rng.gen_range(0.0..140.0)),
-                        )
+                        Transform2::from_translation(damage_receive_event.contact.unwrap_or_else(
+                            || {
+                                unit_transform.translation().truncate()
+                                    + Vec2::new(
+                                        rng.gen_range(-20.0..20.0),
+                                        rng.gen_range(0.0..140.0),
+                                    )
+                            },
+                        ))
                         .with_scale(Vec2::splat(0.5)),
                         Depth::from(DEPTH_BLOOD_FX),
                         TextureAtlasFx::new(5),
@@ -659,173 +617,298 @@ fn unit_damage_fx(
 }
 
 fn unit_update(
-    mut unit_query: Query<(&mut Transform2, &Unit, &Feeler)>,
+    mut unit_query: Query<(&mut Transform2, &Unit, &Feeler, &StatusEffects)>,
     time: Res<FixedTime>,
     battle_state: Res<BattleState>,
 ) {
-    for (mut unit_transform, unit, unit_feeler) in unit_query.iter_mut() {
+    for (mut unit_transform, unit, unit_feeler, status_effects) in unit_query.iter_mut() {
         let should_walk = battle_state.phase() != BattlePhase::PreBattle
             && (!unit_feeler.feeling || !unit.stats.stop_on_feeler);
         if should_walk {
-            unit_transform.translation.x +=
-                time.period.as_secs_f32() * unit.speed() * unit_transform.scale.x.signum();
+            let distance = time.period.as_secs_f32() * unit.speed(status_effects);
+            unit_transform.translation.x += distance * unit_transform.scale.x.signum();
+            if let Some(target) = unit.target {
+                let lane_distance = distance * UNIT_LANE_CHANGE_SPEED;
+                unit_transform.translation.y +=
+                    target.offset.y.clamp(-lane_distance, lane_distance);
+            }
         }
     }
 }
 
+#[derive(Clone, Copy)]
+struct UnitVolley {
+    position: Vec2,
+    time_left: f32,
+}
+
+#[derive(Default)]
+struct UnitVolleys {
+    volleys: EnumMap<Team, Option<UnitVolley>>,
+}
+
 fn unit_attack(
+    mut local: Local<UnitVolleys>,
     mut commands: Commands,
-    mut spine_events: EventReader<SpineEvent>,
+    mut unit_query: Query<(
+        Entity,
+        &mut Unit,
+        &BattleModifiers,
+        &Feeler,
+        &StatusEffects,
+        &GlobalTransform,
+    )>,
     battle_state: Res<BattleState>,
-    unit_query: Query<(Entity, &Unit, &GlobalTransform)>,
-    asset_library: Res<AssetLibrary>,
-    area_of_effect_targeting: Res<AreaOfEffectTargeting>,
+    asset_library: Option<Res<AssetLibrary>>,
+    broadphase: Res<Broadphase>,
+    time: Res<FixedTime>,
+    unit_definitions: Res<UnitDefinitions>,
+    mut battle_rng: ResMut<BattleRng>,
 ) {
-    let mut rng = thread_rng();
-    for spine_event in spine_events.iter() {
-        if let SpineEvent::Event {
-            entity: spine_event_entity,
-            name: spine_event_name,
-            ..
-        } = spine_event
+    for volley in local.volleys.values_mut() {
+        *volley = volley
+            .map(|volley| UnitVolley {
+                time_left: volley.time_left - time.period.as_secs_f32(),
+                ..volley
+            })
+            .filter(|volley| volley.time_left > 0. && battle_state.battling());
+    }
+    for (unit_entity, mut unit, modifiers, unit_feeler, status_effects, unit_transform) in
+        unit_query.iter_mut()
+    {
+        if !(status_effects.wants_to_attack(unit_feeler.feeling)
+            && battle_state.phase() == BattlePhase::Battling)
         {
-            if spine_event_name == "attack" {
-                if let Ok((unit_entity, unit, unit_transform)) = unit_query.get(*spine_event_entity)
-                {
-                    let team_modifiers = battle_state.get_modifiers(unit.team);
-                    let damage_multiplier = if team_modifiers[BattleModifier::ExtraAttack] {
-                        1.5
-                    } else {
-                        1.
-                    };
-                    let mut damage_modifiers = DamageModifiers::default();
-                    if team_modifiers[BattleModifier::Fire] {
-                        damage_modifiers[DamageModifier::Fire] = true;
-                    }
-                    if team_modifiers[BattleModifier::Ice] {
-                        damage_modifiers[DamageModifier::Ice] = true;
-                    }
-                    if team_modifiers[BattleModifier::Wet] {
-                        damage_modifiers[DamageModifier::Wet] = true;
-                    }
-                    let friendly_fire = if (team_modifiers[BattleModifier::FriendlyFire]
-                        || unit.attributes.contains(Attributes::MAY_FRIENDLY_FIRE))
-                        && rng.gen_bool(0.25)
-                    {
-                        true
-                    } else {
-                        false
+            unit.attack_time = None;
+            continue;
+        }
+        let mut attack_time = unit.attack_time.unwrap_or(0.);
+        let attack_count = unit.stats.attack_timeline.advance(
+            &mut attack_time,
+            time.period.as_secs_f32() * unit.attack_speed(modifiers),
+        );
+        unit.attack_time = Some(attack_time);
+        for _ in 0..attack_count {
+            let damage_multiplier = modifiers.magnitude(BattleModifier::ExtraAttack);
+            let mut damage_modifiers = DamageModifiers::default();
+            if modifiers.active(BattleModifier::Fire) {
+                damage_modifiers[DamageModifier::Fire] = true;
+            }
+            if modifiers.active(BattleModifier::Ice) {
+                damage_modifiers[DamageModifier::Ice] = true;
+            }
+            if modifiers.active(BattleModifier::Wet) {
+                damage_modifiers[DamageModifier::Wet] = true;
+            }
+            let friendly_fire_chance = if modifiers.active(BattleModifier::FriendlyFire) {
+                modifiers.magnitude(BattleModifier::FriendlyFire)
+            } else if unit.attributes.contains(Attributes::MAY_FRIENDLY_FIRE) {
+                BattleModifier::FriendlyFire.magnitude(1)
+            } else {
+                0.
+            };
+            let friendly_fire =
+                friendly_fire_chance > 0. && battle_rng.gen_bool(friendly_fire_chance as f64);
+            let attack_stats = *unit_definitions.attack_stats(unit.stats.attack);
+            let mut hurt_flags = unit.team.hurt_flags();
+            if friendly_fire {
+                hurt_flags |= unit.team.hit_flags();
+            }
+            if let Some(asset_library) = asset_library.as_ref() {
+                commands.spawn(TempSfxBundle {
+                    audio_source: AudioPlusSource::new(match unit.kind {
+                        UnitKind::Peasant => asset_library.sounds.unit_peasant_attack.clone(),
+                        UnitKind::Warrior => asset_library.sounds.unit_warrior_attack.clone(),
+                        UnitKind::Archer => asset_library.sounds.unit_archer_attack.clone(),
+                        UnitKind::Mage => asset_library.sounds.unit_mage_attack.clone(),
+                        UnitKind::Brute => asset_library.sounds.unit_brute_attack.clone(),
+                    })
+                    .as_playing(),
+                    transform2: Transform2::from_translation(
+                        unit_transform.translation().truncate(),
+                    ),
+                    ..Default::default()
+                });
+            }
+            let source = unit.damage_source(DamageCause::Attack);
+            match attack_stats.hurt_box_kind {
+                AttackHurtBoxKind::OffsetRect {
+                    offset: hurt_box_offset,
+                    size: hurt_box_size,
+                } => {
+                    commands.spawn((
+                        HurtBox {
+                            flags: hurt_flags,
+                            shape: CollisionShape::Rect {
+                                offset: Vec2::new(0., hurt_box_size.y * 0.25),
+                                size: hurt_box_size,
+                            },
+                            damage: attack_stats.damage * damage_multiplier,
+                            damage_kind: attack_stats.damage_kind,
+                            damage_modifiers,
+                            max_hits: attack_stats.hit_count,
+                            ignore_entity: unit_entity,
+                            slow: modifiers.active(BattleModifier::Ice),
+                            knockback: attack_stats.knockback,
+                            source,
+                        },
+                        TransformBundle::default(),
+                        Transform2::from_translation(
+                            unit_transform.translation().truncate()
+                                + Vec2::new(
+                                    hurt_box_offset * unit.move_direction(status_effects),
+                                    0.,
+                                ),
+                        ),
+                        FramesToLive::new(2),
+                    ));
+                }
+                AttackHurtBoxKind::AreaOfEffect {
+                    size: hurt_box_size,
+                    damage_start,
+                    damage_end,
+                    duration,
+                } => {
+                    let unit_position = unit_transform.translation().truncate();
+                    let area_shape = CollisionShape::Rect {
+                        offset: Vec2::ZERO,
+                        size: hurt_box_size,
                     };
-                    let attack_stats = unit.stats.attack.stats();
-                    let mut hurt_flags = unit.team.hurt_flags();
-                    if friendly_fire {
-                        hurt_flags |= unit.team.hit_flags();
-                    }
-                    commands.spawn(TempSfxBundle {
-                        audio_source: AudioPlusSource::new(match unit.kind {
-                            UnitKind::Peasant => asset_library.sounds.unit_peasant_attack.clone(),
-                            UnitKind::Warrior => asset_library.sounds.unit_warrior_attack.clone(),
-                            UnitKind::Archer => asset_library.sounds.unit_archer_attack.clone(),
-                            UnitKind::Mage => asset_library.sounds.unit_mage_attack.clone(),
-                            UnitKind::Brute => asset_library.sounds.unit_brute_attack.clone(),
+                    let area_target = area_of_effect_target(
+                        &broadphase,
+                        unit.team.hurt_flags(),
+                        unit_feeler.shape.at(unit_position),
+                        area_shape,
+                    )
+                    .or_else(|| {
+                        let position = unit_position + unit.target?.offset;
+                        Some(AreaOfEffectTarget {
+                            position,
+                            expected_hits: expected_hits(
+                                &broadphase,
+                                unit.team.hurt_flags(),
+                                area_shape.at(position),
+                            ),
                         })
-                        .as_playing(),
-                        transform2: Transform2::from_translation(
-                            unit_transform.translation().truncate(),
-                        ),
-                        ..Default::default()
                     });
-                    match attack_stats.hurt_box_kind {
-                        AttackHurtBoxKind::OffsetRect {
-                            offset: hurt_box_offset,
-                            size: hurt_box_size,
-                        } => {
-                            commands.spawn((
+                    if let Some(area_target) = area_target {
+                        let mut target_position = area_target.position;
+                        let spread = unit.accuracy_spread(status_effects);
+                        if spread > 0. {
+                            target_position.x += battle_rng.gen_range(-spread..spread);
+                        }
+                        let mut area_of_effect_entity = commands.spawn((
+                            HurtBoxTimeline::new(
                                 HurtBox {
                                     flags: hurt_flags,
-                                    shape: CollisionShape::Rect {
-                                        offset: Vec2::new(0., hurt_box_size.y * 0.25),
-                                        size: hurt_box_size,
-                                    },
+                                    shape: area_shape,
                                     damage: attack_stats.damage * damage_multiplier,
                                     damage_kind: attack_stats.damage_kind,
                                     damage_modifiers,
                                     max_hits: attack_stats.hit_count,
                                     ignore_entity: unit_entity,
-                                    slow: team_modifiers[BattleModifier::Ice],
+                                    slow: modifiers.active(BattleModifier::Ice),
+                                    knockback: attack_stats.knockback,
+                                    source,
                                 },
-                                TransformBundle::default(),
-                                Transform2::from_translation(
-                                    unit_transform.translation().truncate()
-                                        + Vec2::new(hurt_box_offset * unit.move_direction(), 0.),
-                                ),
-                                FramesToLive::new(2),
-                            ));
-                        }
-                        AttackHurtBoxKind::AreaOfEffect {
-                            size: hurt_box_size,
-                        } => {
-                            if let Some(target_position) =
-                                area_of_effect_targeting.get_target(unit.team.opposite_team())
-                            {
-                                commands.spawn((
+                                damage_start,
+                                damage_end,
+                                duration,
+                            ),
+                            SpatialBundle::default(),
+                            Transform2::from_translation(target_position),
+                            area_target,
+                        ));
+                        if let Some(asset_library) = asset_library.as_ref() {
+                            area_of_effect_entity.with_children(|parent| {
+                                parent.spawn((
                                     SpineBundle {
                                         skeleton: asset_library.spine_attack_magic.clone(),
                                         ..Default::default()
                                     },
-                                    SpineAttack {
-                                        hurt_box: HurtBox {
-                                            flags: hurt_flags,
-                                            shape: CollisionShape::Rect {
-                                                offset: Vec2::ZERO,
-                                                size: hurt_box_size,
-                                            },
-                                            damage: attack_stats.damage * damage_multiplier,
-                                            damage_kind: attack_stats.damage_kind,
-                                            damage_modifiers,
-                                            max_hits: attack_stats.hit_count,
-                                            ignore_entity: unit_entity,
-                                            slow: team_modifiers[BattleModifier::Ice],
-                                        },
-                                    },
                                     SpineFx,
-                                    Transform2::from_translation(
-                                        target_position
-                                            + Vec2::new(rng.gen_range(-200.0..200.0), 0.),
-                                    ),
+                                    Transform2::default(),
                                 ));
-                            }
+                            });
                         }
-                        AttackHurtBoxKind::Projectile => {
-                            commands.spawn((
-                                HurtBox {
-                                    flags: hurt_flags,
-                                    shape: CollisionShape::Rect {
-                                        offset: Vec2::ZERO,
-                                        size: Vec2::new(60., 10.),
-                                    },
-                                    damage: attack_stats.damage * damage_multiplier,
-                                    damage_kind: attack_stats.damage_kind,
-                                    damage_modifiers,
-                                    max_hits: attack_stats.hit_count,
-                                    ignore_entity: unit_entity,
-                                    slow: team_modifiers[BattleModifier::Ice],
-                                },
-                                HurtBoxDespawner,
-                                SpriteBundle {
-                                    texture: asset_library.image_arrow.clone(),
-                                    ..Default::default()
-                                },
-                                Transform2::from_translation(
-                                    unit_transform.translation().truncate() + Vec2::new(0., 90.),
-                                ),
-                                Projectile {
-                                    velocity: Vec2::new(unit.move_direction() * 2500., 300.),
-                                },
-                                FramesToLive::new(100),
-                                Depth::from(DEPTH_PROJECTILE),
-                            ));
+                    }
+                }
+                AttackHurtBoxKind::Projectile => {
+                    let unit_position = unit_transform.translation().truncate();
+                    let launch_position = unit_position + Vec2::new(0., PROJECTILE_HEIGHT);
+                    // archers firing together aim for the same spot as the first one in the volley,
+                    // as long as it is in front of them and within half their feeler
+                    let max_range = unit.stats.feeler_size.x * 0.5;
+                    let move_direction = unit.move_direction(status_effects);
+                    let volley = &mut local.volleys[unit.team];
+                    let aim_position = match (*volley, unit.target) {
+                        (Some(volley), _)
+                            if (volley.position.x - unit_position.x) * move_direction >= 0.
+                                && volley.position.distance(unit_position) < max_range =>
+                        {
+                            Some(volley.position)
+                        }
+                        (_, Some(target)) => {
+                            let position = unit_position + target.offset;
+                            *volley = Some(UnitVolley {
+                                position,
+                                time_left: VOLLEY_DURATION,
+                            });
+                            Some(position)
                         }
+                        _ => None,
+                    };
+                    let (velocity, ground) = if let Some(mut aim_position) = aim_position {
+                        let spread = unit.accuracy_spread(status_effects);
+                        if spread > 0. {
+                            aim_position.x += battle_rng.gen_range(-spread..spread);
+                        }
+                        (
+                            ballistic_velocity(
+                                aim_position - launch_position,
+                                PROJECTILE_SPEED,
+                                PROJECTILE_GRAVITY,
+                            ),
+                            aim_position.y,
+                        )
+                    } else {
+                        (
+                            Vec2::new(move_direction * PROJECTILE_SPEED, PROJECTILE_LIFT),
+                            unit_position.y,
+                        )
+                    };
+                    let mut projectile_entity = commands.spawn((
+                        HurtBox {
+                            flags: hurt_flags,
+                            shape: CollisionShape::Rect {
+                                offset: Vec2::ZERO,
+                                size: Vec2::new(60., 10.),
+                            },
+                            damage: attack_stats.damage * damage_multiplier,
+                            damage_kind: attack_stats.damage_kind,
+                            damage_modifiers,
+                            max_hits: attack_stats.hit_count,
+                            ignore_entity: unit_entity,
+                            slow: modifiers.active(BattleModifier::Ice),
+                            knockback: attack_stats.knockback,
+                            source,
+                        },
+                        HurtBoxDespawner,
+                        TransformBundle::default(),
+                        Transform2::from_translation(launch_position),
+                        Projectile {
+                            velocity,
+                            gravity: PROJECTILE_GRAVITY,
+                            ground,
+                            ..Default::default()
+                        },
+                        Depth::from(DEPTH_PROJECTILE),
+                    ));
+                    if let Some(asset_library) = asset_library.as_ref() {
+                        projectile_entity.insert(SpriteBundle {
+                            texture: asset_library.image_arrow.clone(),
+                            ..Default::default()
+                        });
                     }
                 }
             }
@@ -837,59 +920,45 @@ fn unit_die(
     mut health_die_events: EventReader<HealthDieEvent>,
     mut commands: Commands,
     unit_query: Query<(&GlobalTransform, &Unit)>,
-    asset_library: Res<AssetLibrary>,
+    asset_library: Option<Res<AssetLibrary>>,
 ) {
     for health_die_event in health_die_events.iter() {
         if let Ok((unit_transform, unit)) = unit_query.get(health_die_event.entity) {
             if let Some(entity) = commands.get_entity(health_die_event.entity) {
                 entity.despawn_recursive();
             }
-            commands.spawn(TempSfxBundle {
-                audio_source: AudioPlusSource::new(match unit.kind {
-                    UnitKind::Peasant => asset_library.sounds.unit_peasant_die.clone(),
-                    UnitKind::Warrior => asset_library.sounds.unit_warrior_die.clone(),
-                    UnitKind::Archer => asset_library.sounds.unit_archer_die.clone(),
-                    UnitKind::Mage => asset_library.sounds.unit_mage_die.clone(),
-                    UnitKind::Brute => asset_library.sounds.unit_brute_die.clone(),
-                })
-                .as_playing(),
-                transform2: Transform2::from_translation(unit_transform.translation().truncate()),
-                ..Default::default()
-            });
-        }
-    }
-}
-
-fn unit_cowardly(
-    mut unit_query: Query<&mut Unit>,
-    mut damage_receive_events: EventReader<DamageReceiveEvent>,
-    battle_state: Res<BattleState>,
-) {
-    let mut rng = thread_rng();
-    for damage_receive_event in damage_receive_events.iter() {
-        if let Ok(mut unit) = unit_query.get_mut(damage_receive_event.entity) {
-            if rng.gen_bool(unit.stats.retreat_chance as f64)
-                && (unit.attributes.contains(Attributes::MAY_RETREAT)
-                    || battle_state.get_modifiers(unit.team)[BattleModifier::Cowardly])
-            {
-                unit.retreating = true;
+            if let Some(asset_library) = asset_library.as_ref() {
+                commands.spawn(TempSfxBundle {
+                    audio_source: AudioPlusSource::new(match unit.kind {
+                        UnitKind::Peasant => asset_library.sounds.unit_peasant_die.clone(),
+                        UnitKind::Warrior => asset_library.sounds.unit_warrior_die.clone(),
+                        UnitKind::Archer => asset_library.sounds.unit_archer_die.clone(),
+                        UnitKind::Mage => asset_library.sounds.unit_mage_die.clone(),
+                        UnitKind::Brute => asset_library.sounds.unit_brute_die.clone(),
+                    })
+                    .as_playing(),
+                    transform2: Transform2::from_translation(
+                        unit_transform.translation().truncate(),
+                    ),
+                    ..Default::default()
+                });
             }
         }
     }
 }
 
-fn unit_update_sprite_direction(mut unit_query: Query<(&mut Transform2, &Unit)>) {
-    for (mut unit_transform, unit) in unit_query.iter_mut() {
-        unit_transform.scale.x = UNIT_SCALE * unit.move_direction();
+fn unit_update_sprite_direction(mut unit_query: Query<(&mut Transform2, &Unit, &StatusEffects)>) {
+    for (mut unit_transform, unit, status_effects) in unit_query.iter_mut() {
+        unit_transform.scale.x = UNIT_SCALE * unit.move_direction(status_effects);
     }
 }
 
 fn unit_update_animations(
-    mut unit_query: Query<(&mut Spine, &Unit, &Feeler)>,
+    mut unit_query: Query<(&mut Spine, &Unit, &BattleModifiers, &Feeler)>,
     battle_state: Res<BattleState>,
 ) {
     let mut rng = thread_rng();
-    for (mut unit_spine, unit, unit_feeler) in unit_query.iter_mut() {
+    for (mut unit_spine, unit, modifiers, unit_feeler) in unit_query.iter_mut() {
         let should_walk = battle_state.phase() != BattlePhase::PreBattle
             && (!unit_feeler.feeling || !unit.stats.stop_on_feeler);
         let current_animation_name = unit_spine
@@ -924,26 +993,23 @@ fn unit_update_animations(
             .track_at_index(UNIT_TRACK_ATTACK)
             .map(|track| track.animation().name().to_owned())
             .unwrap_or(String::new());
-        if (unit.can_attack() && unit_feeler.feeling || unit.blind)
-            && battle_state.phase() == BattlePhase::Battling
-        {
+        if let Some(attack_time) = unit.attack_time {
             if current_animation_name != "attack" {
                 if let Ok(mut track) = unit_spine.animation_state.set_animation_by_name(
                     UNIT_TRACK_ATTACK as i32,
                     "attack",
                     true,
                 ) {
-                    let slowness = battle_state.get_modifiers(unit.team)[BattleModifier::Slowness];
-                    let quick_attack =
-                        battle_state.get_modifiers(unit.team)[BattleModifier::QuickAttack];
-                    if slowness && !quick_attack {
-                        track.set_timescale(0.5);
-                    } else if !slowness && quick_attack {
-                        track.set_timescale(2.);
-                    } else {
-                        track.set_timescale(1.);
-                    }
+                    track.set_track_time(attack_time);
+                    track.set_timescale(unit.attack_speed(modifiers));
                 }
+            } else if let Some(mut track) = unit_spine
+                .animation_state
+                .track_at_index_mut(UNIT_TRACK_ATTACK)
+            {
+                // the attack timeline decides when units swing, the animation only follows it
+                track.set_track_time(attack_time);
+                track.set_timescale(unit.attack_speed(modifiers));
             }
         } else {
             if current_animation_name != "attack_stop" {
@@ -957,10 +1023,13 @@ fn unit_update_animations(
     }
 }
 
-fn unit_update_feeler(mut unit_query: Query<(&mut Feeler, &Unit)>) {
-    for (mut unit_feeler, unit) in unit_query.iter_mut() {
+fn unit_update_feeler(mut unit_query: Query<(&mut Feeler, &Unit, &StatusEffects)>) {
+    for (mut unit_feeler, unit, status_effects) in unit_query.iter_mut() {
         unit_feeler.shape = CollisionShape::Rect {
-            offset: Vec2::new(unit.stats.feeler_size.x * 0.5 * unit.move_direction(), 0.),
+            offset: Vec2::new(
+                unit.stats.feeler_size.x * 0.5 * unit.move_direction(status_effects),
+                0.,
+            ),
             size: unit.stats.feeler_size,
         };
     }
@@ -974,66 +1043,46 @@ struct UnitCombustion {
 
 fn unit_combust(
     mut local: Local<UnitCombustion>,
-    mut unit_query: Query<(Entity, &mut Unit, &Children)>,
-    mut unit_fire_query: Query<
-        (
-            &mut Visibility,
-            &mut TextureAtlasSprite,
-            &mut AudioPlusSource,
-        ),
-        With<UnitFire>,
-    >,
-    mut damage_inflict_events: EventWriter<DamageInflictEvent>,
+    mut unit_query: Query<(&Unit, &BattleModifiers, &mut StatusEffects)>,
     battle_state: Res<BattleState>,
     time: Res<FixedTime>,
-    frame_time: Res<Time>,
+    mut battle_rng: ResMut<BattleRng>,
 ) {
-    let mut rng = thread_rng();
-    for (unit_entity, unit, _) in unit_query.iter_mut() {
-        if unit.attributes.contains(Attributes::ON_FIRE) {
-            if battle_state.battling() && battle_state.phase() == BattlePhase::Battling {
-                damage_inflict_events.send(DamageInflictEvent {
-                    entity: unit_entity,
-                    damage: time.period.as_secs_f32() * 5.,
-                    slow: false,
-                });
-            }
-        }
-    }
-    for (_, mut unit_fire_sprite, _) in unit_fire_query.iter_mut() {
-        unit_fire_sprite.index = ((frame_time.elapsed_seconds() * 5.) as usize) % 4;
-    }
     for team in Team::iter() {
         if battle_state.battling() && battle_state.phase() == BattlePhase::Battling {
             if local.time_until_next_combustion[team] == 0. {
-                local.time_until_next_combustion[team] = rng.gen_range(0.5..1.);
+                local.time_until_next_combustion[team] = battle_rng.gen_range(0.5..1.);
             }
-            if battle_state.get_modifiers(team)[BattleModifier::Combustion] {
+            let magnitude = strongest_modifier(
+                team,
+                BattleModifier::Combustion,
+                unit_query
+                    .iter()
+                    .map(|(unit, modifiers, _)| (unit, modifiers)),
+            );
+            if let Some(magnitude) = magnitude {
                 let mut combust = false;
                 if local.time_since_last_combustion[team] > local.time_until_next_combustion[team] {
                     combust = true;
                     local.time_since_last_combustion[team] = 0.;
-                    local.time_until_next_combustion[team] = rng.gen_range(0.5..2.0);
+                    local.time_until_next_combustion[team] = battle_rng.gen_range(0.5..2.0);
                 }
-                local.time_since_last_combustion[team] += time.period.as_secs_f32();
+                local.time_since_last_combustion[team] += time.period.as_secs_f32() * magnitude;
                 if combust {
                     let mut units = unit_query
                         .iter_mut()
-                        .filter(|(_, unit, _)| {
-                            unit.team == team && !unit.attributes.contains(Attributes::ON_FIRE)
+                        .filter(|(unit, modifiers, status_effects)| {
+                            unit.team == team
+                                && modifiers.active(BattleModifier::Combustion)
+                                && !status_effects.has(StatusEffectKind::Burning)
                         })
                         .collect::<Vec<_>>();
-                    units.shuffle(&mut rng);
-                    if let Some((_, mut unit, unit_children)) = units.into_iter().nth(0) {
-                        for child in unit_children.iter() {
-                            if let Ok((mut unit_fire_visibility, _, mut unit_fire_audio_source)) =
-                                unit_fire_query.get_mut(*child)
-                            {
-                                *unit_fire_visibility = Visibility::Visible;
-                                unit_fire_audio_source.play();
-                            }
-                        }
-                        unit.attributes |= Attributes::ON_FIRE;
+                    units.shuffle(&mut *battle_rng);
+                    if let Some((unit, _, mut status_effects)) = units.into_iter().nth(0) {
+                        status_effects.apply(StatusEffect::new(
+                            StatusEffectKind::Burning,
+                            unit.damage_source(DamageCause::Combustion),
+                        ));
                     }
                 }
             }
@@ -1044,6 +1093,37 @@ fn unit_combust(
     }
 }
 
+fn unit_update_fire(
+    unit_query: Query<(&StatusEffects, &Children), With<Unit>>,
+    mut unit_fire_query: Query<
+        (
+            &mut Visibility,
+            &mut TextureAtlasSprite,
+            &mut AudioPlusSource,
+        ),
+        With<UnitFire>,
+    >,
+    frame_time: Res<Time>,
+) {
+    for (status_effects, unit_children) in unit_query.iter() {
+        if status_effects.has(StatusEffectKind::Burning) {
+            for child in unit_children.iter() {
+                if let Ok((mut unit_fire_visibility, _, mut unit_fire_audio_source)) =
+                    unit_fire_query.get_mut(*child)
+                {
+                    if *unit_fire_visibility == Visibility::Hidden {
+                        *unit_fire_visibility = Visibility::Visible;
+                        unit_fire_audio_source.play();
+                    }
+                }
+            }
+        }
+    }
+    for (_, mut unit_fire_sprite, _) in unit_fire_query.iter_mut() {
+        unit_fire_sprite.index = ((frame_time.elapsed_seconds() * 5.) as usize) % 4;
+    }
+}
+
 #[derive(Default)]
 struct UnitExplosion {
     time_since_last_explosion: EnumMap<Team, f32>,
@@ -1052,66 +1132,80 @@ struct UnitExplosion {
 
 fn unit_explode(
     mut local: Local<UnitExplosion>,
-    mut unit_query: Query<(Entity, &mut Unit, &GlobalTransform)>,
+    mut unit_query: Query<(Entity, &mut Unit, &BattleModifiers, &GlobalTransform)>,
     mut damage_inflict_events: EventWriter<DamageInflictEvent>,
     mut commands: Commands,
     battle_state: Res<BattleState>,
     time: Res<FixedTime>,
-    asset_library: Res<AssetLibrary>,
+    asset_library: Option<Res<AssetLibrary>>,
+    mut battle_rng: ResMut<BattleRng>,
 ) {
-    let mut rng = thread_rng();
     for team in Team::iter() {
         if battle_state.battling() && battle_state.phase() == BattlePhase::Battling {
             if local.time_until_next_explosion[team] == 0. {
-                local.time_until_next_explosion[team] = rng.gen_range(0.5..1.);
+                local.time_until_next_explosion[team] = battle_rng.gen_range(0.5..1.);
             }
-            if battle_state.get_modifiers(team)[BattleModifier::Explosive] {
+            let magnitude = strongest_modifier(
+                team,
+                BattleModifier::Explosive,
+                unit_query
+                    .iter()
+                    .map(|(_, unit, modifiers, _)| (unit, modifiers)),
+            );
+            if let Some(magnitude) = magnitude {
                 let mut combust = false;
                 if local.time_since_last_explosion[team] > local.time_until_next_explosion[team] {
                     combust = true;
                     local.time_since_last_explosion[team] = 0.;
-                    local.time_until_next_explosion[team] = rng.gen_range(1.5..3.0);
+                    local.time_until_next_explosion[team] = battle_rng.gen_range(1.5..3.0);
                 }
-                local.time_since_last_explosion[team] += time.period.as_secs_f32();
+                local.time_since_last_explosion[team] += time.period.as_secs_f32() * magnitude;
                 if combust {
                     let mut units = unit_query
                         .iter_mut()
-                        .filter(|(_, unit, _)| unit.team == team)
+                        .filter(|(_, unit, modifiers, _)| {
+                            unit.team == team && modifiers.active(BattleModifier::Explosive)
+                        })
                         .collect::<Vec<_>>();
-                    units.shuffle(&mut rng);
-                    if let Some((unit_entity, _, unit_transform)) = units.into_iter().nth(0) {
-                        let dummy_entity = commands
-                            .spawn((
+                    units.shuffle(&mut *battle_rng);
+                    if let Some((unit_entity, unit, _, unit_transform)) = units.into_iter().nth(0) {
+                        let source = unit.damage_source(DamageCause::Explosion);
+                        let explosion_position = unit_transform.translation().truncate();
+                        let fx_position = explosion_position
+                            + Vec2::new(
+                                battle_rng.gen_range(-20.0..20.0),
+                                battle_rng.gen_range(0.0..140.0),
+                            );
+                        if let Some(asset_library) = asset_library.as_ref() {
+                            commands.spawn((
                                 SpriteSheetBundle {
                                     texture_atlas: asset_library.image_atlas_explosion.clone(),
                                     ..Default::default()
                                 },
-                                Transform2::from_translation(
-                                    unit_transform.translation().truncate()
-                                        + Vec2::new(
-                                            rng.gen_range(-20.0..20.0),
-                                            rng.gen_range(0.0..140.0),
-                                        ),
-                                )
-                                .with_scale(Vec2::splat(1.)),
+                                Transform2::from_translation(fx_position)
+                                    .with_scale(Vec2::splat(1.)),
                                 Depth::from(DEPTH_EXPLOSION_FX),
                                 TextureAtlasFx::new(5),
-                            ))
-                            .id();
-                        commands.spawn(TempSfxBundle {
-                            audio_source: AudioPlusSource::new(
-                                asset_library.sounds.unit_explode.clone(),
-                            )
-                            .as_playing(),
-                            transform2: Transform2::from_translation(
-                                unit_transform.translation().truncate(),
-                            ),
-                            ..Default::default()
-                        });
+                            ));
+                            commands.spawn(TempSfxBundle {
+                                audio_source: AudioPlusSource::new(
+                                    asset_library.sounds.unit_explode.clone(),
+                                )
+                                .as_playing(),
+                                transform2: Transform2::from_translation(explosion_position),
+                                ..Default::default()
+                            });
+                        }
                         damage_inflict_events.send(DamageInflictEvent {
                             entity: unit_entity,
                             damage: 999999.,
+                            damage_kind: DamageKind::Magic,
+                            damage_modifiers: DamageModifiers::default(),
                             slow: false,
+                            resisted: false,
+                            contact: None,
+                            knockback: Vec2::ZERO,
+                            source,
                         });
                         commands.spawn((
                             HurtBox {
@@ -1124,11 +1218,13 @@ fn unit_explode(
                                 damage_kind: DamageKind::Magic,
                                 damage_modifiers: enum_map! { DamageModifier::Fire => true, _ => false},
                                 max_hits: 2,
-                                ignore_entity: dummy_entity,
+                                ignore_entity: Entity::PLACEHOLDER,
                                 slow: false,
+                                knockback: EXPLOSION_KNOCKBACK,
+                                source,
                             },
                             TransformBundle::default(),
-                            Transform2::from_translation(unit_transform.translation().truncate()),
+                            Transform2::from_translation(explosion_position),
                             FramesToLive::new(10),
                             Depth::Exact(1.),
                         ));
@@ -1150,7 +1246,7 @@ struct UnitDrip {
 
 fn unit_drip(
     mut local: Local<UnitDrip>,
-    mut unit_query: Query<(Entity, &mut Unit, &GlobalTransform)>,
+    mut unit_query: Query<(&Unit, &BattleModifiers, &StatusEffects, &GlobalTransform)>,
     mut commands: Commands,
     battle_state: Res<BattleState>,
     time: Res<FixedTime>,
@@ -1162,7 +1258,15 @@ fn unit_drip(
             if local.time_until_next_drip[team] == 0. {
                 local.time_until_next_drip[team] = rng.gen_range(0.1..0.4);
             }
-            if battle_state.get_modifiers(team)[BattleModifier::Wet] {
+            let wet = strongest_modifier(
+                team,
+                BattleModifier::Wet,
+                unit_query
+                    .iter()
+                    .map(|(unit, modifiers, _, _)| (unit, modifiers)),
+            )
+            .is_some();
+            if wet {
                 let mut combust = false;
                 if local.time_since_last_drip[team] > local.time_until_next_drip[team] {
                     combust = true;
@@ -1173,10 +1277,14 @@ fn unit_drip(
                 if combust {
                     let mut units = unit_query
                         .iter_mut()
-                        .filter(|(_, unit, _)| unit.team == team)
+                        .filter(|(unit, modifiers, status_effects, _)| {
+                            unit.team == team
+                                && modifiers.active(BattleModifier::Wet)
+                                && status_effects.has(StatusEffectKind::Soaked)
+                        })
                         .collect::<Vec<_>>();
                     units.shuffle(&mut rng);
-                    if let Some((_, _, unit_transform)) = units.into_iter().nth(0) {
+                    if let Some((_, _, _, unit_transform)) = units.into_iter().nth(0) {
                         commands.spawn((
                             SpriteSheetBundle {
                                 texture_atlas: asset_library.image_atlas_wet.clone(),
@@ -1202,3 +1310,15 @@ fn unit_drip(
         }
     }
 }
+
+// units can carry different modifiers, so team-wide timers run at the strongest stack among them
+fn strongest_modifier<'a>(
+    team: Team,
+    modifier: BattleModifier,
+    units: impl Iterator<Item = (&'a Unit, &'a BattleModifiers)>,
+) -> Option<f32> {
+    units
+        .filter(|(unit, modifiers)| unit.team == team && modifiers.active(modifier))
+        .map(|(_, modifiers)| modifiers.magnitude(modifier))
+        .reduce(f32::max)
+}
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{Team, UnitKind};
+
+pub const FORMATION_ROWS: usize = 3;
+pub const FORMATION_LANES: usize = 3;
+pub const SPAWN_X_DISTANCE: f32 = 400.;
+pub const SPAWN_Y_MIN: f32 = -400.;
+pub const SPAWN_Y_MAX: f32 = -200.;
+
+const ROW_DEPTH: f32 = 300.;
+
+// row 0 is the front line, lane 0 is the top of the battlefield
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Formation {
+    cells: [[Option<UnitKind>; FORMATION_LANES]; FORMATION_ROWS],
+}
+
+impl Formation {
+    pub fn get(&self, row: usize, lane: usize) -> Option<UnitKind> {
+        self.cells[row][lane]
+    }
+
+    pub fn set(&mut self, row: usize, lane: usize, unit_kind: Option<UnitKind>) {
+        self.cells[row][lane] = unit_kind;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().flatten().all(|cell| cell.is_none())
+    }
+
+    pub fn cells_for(&self, unit_kind: UnitKind) -> Vec<(usize, usize)> {
+        let mut cells = vec![];
+        for row in 0..FORMATION_ROWS {
+            for lane in 0..FORMATION_LANES {
+                if self.cells[row][lane] == Some(unit_kind) {
+                    cells.push((row, lane));
+                }
+            }
+        }
+        cells
+    }
+
+    pub fn spawn_position(team: Team, row: usize, lane: usize, rng: &mut impl Rng) -> Vec2 {
+        let lane_height = (SPAWN_Y_MAX - SPAWN_Y_MIN) / FORMATION_LANES as f32;
+        let x_min = row as f32 * ROW_DEPTH;
+        let y_max = SPAWN_Y_MAX - lane as f32 * lane_height;
+        let x =
+            (rng.gen_range(x_min..(x_min + ROW_DEPTH)) + SPAWN_X_DISTANCE) * -team.move_direction();
+        let y = rng.gen_range((y_max - lane_height)..y_max);
+        Vec2::new(x, y)
+    }
+}
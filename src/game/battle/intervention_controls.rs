@@ -0,0 +1,391 @@
+use bevy::prelude::*;
+
+use crate::{
+    AppState, AssetLibrary, BattlefieldSpawnEvent, CameraAnchor, Clickable, ClickableSystem,
+    CollisionShape, Cursor, Depth, DepthLayer, EventSet, GameState, Health, Intervention,
+    InterventionEvent, InterventionKind, Interventions, Item, ItemDefinitions, ReplayState, Sfx,
+    SfxKind, SpawnSet, Team, Transform2, Unit, SPICE_RADIUS,
+};
+
+const RETREAT_HORN_PICK_DISTANCE: f32 = 150.;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum InterventionControlsSystem {
+    Spawn,
+    Update,
+    ConsumeSpice,
+}
+
+pub struct InterventionControlsPlugin;
+
+impl Plugin for InterventionControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InterventionControls>()
+            .add_system(
+                intervention_controls_spawn
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(InterventionControlsSystem::Spawn)
+                    .in_set(SpawnSet)
+                    .after(EventSet::<BattlefieldSpawnEvent>::Sender),
+            )
+            .add_system(
+                intervention_controls_update
+                    .in_set(InterventionControlsSystem::Update)
+                    .after(ClickableSystem),
+            )
+            .add_system(
+                intervention_controls_consume_spice
+                    .in_set(InterventionControlsSystem::ConsumeSpice)
+                    .before(InterventionControlsSystem::Update),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct InterventionControls {
+    selected_spice: usize,
+    targeting: Option<InterventionKind>,
+    // only leaves the inventory once the throw actually happens
+    thrown_spice: Option<Item>,
+}
+
+#[derive(Component)]
+struct InterventionButton {
+    kind: InterventionButtonKind,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum InterventionButtonKind {
+    Spice,
+    Intervention(InterventionKind),
+}
+
+#[derive(Component)]
+struct InterventionBudgetText;
+
+#[derive(Component)]
+struct InterventionTarget;
+
+#[derive(Component)]
+struct InterventionTargetMarker;
+
+fn intervention_controls_spawn(
+    mut commands: Commands,
+    mut spawn_events: EventReader<BattlefieldSpawnEvent>,
+    mut intervention_controls: ResMut<InterventionControls>,
+    asset_library: Res<AssetLibrary>,
+) {
+    for _ in spawn_events.iter() {
+        *intervention_controls = InterventionControls::default();
+        let text_style = TextStyle {
+            font: asset_library.font_normal.clone(),
+            font_size: 32.,
+            color: Color::GRAY,
+        };
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section("", text_style.clone())
+                    .with_alignment(TextAlignment::Center),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            Transform2::from_xy(-800., -510.),
+            CameraAnchor {
+                offset: Vec2::new(-800., -510.),
+            },
+            Depth::Exact(0.9),
+            InterventionBudgetText,
+        ));
+        let kinds = [
+            (InterventionButtonKind::Spice, 220.),
+            (
+                InterventionButtonKind::Intervention(InterventionKind::ThrowSpice),
+                220.,
+            ),
+            (
+                InterventionButtonKind::Intervention(InterventionKind::RetreatHorn),
+                220.,
+            ),
+            (
+                InterventionButtonKind::Intervention(InterventionKind::Rally),
+                140.,
+            ),
+        ];
+        let mut x = -640.;
+        for (kind, width) in kinds {
+            let offset = Vec2::new(x + width * 0.5, -510.);
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section("", text_style.clone())
+                        .with_alignment(TextAlignment::Center),
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                },
+                Clickable {
+                    shape: CollisionShape::Rect {
+                        offset: Vec2::ZERO,
+                        size: Vec2::new(width, 40.),
+                    },
+                    ..Default::default()
+                },
+                Transform2::from_translation(offset),
+                CameraAnchor { offset },
+                Depth::Exact(0.9),
+                InterventionButton { kind },
+            ));
+            x += width;
+        }
+        // covers the whole battlefield so clicks on it can be used to aim
+        commands.spawn((
+            Clickable {
+                shape: CollisionShape::Rect {
+                    offset: Vec2::ZERO,
+                    size: Vec2::splat(100000.),
+                },
+                ..Default::default()
+            },
+            SpatialBundle::default(),
+            Transform2::default(),
+            InterventionTarget,
+        ));
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(SPICE_RADIUS * 2.)),
+                    color: Color::rgba(1., 0.8, 0.3, 0.2),
+                    ..Default::default()
+                },
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            Transform2::default(),
+            Depth::from(DepthLayer::Front(0.)),
+            InterventionTargetMarker,
+        ));
+    }
+}
+
+fn intervention_controls_update(
+    mut intervention_controls: ResMut<InterventionControls>,
+    mut interventions: ResMut<Interventions>,
+    game_state: Res<GameState>,
+    mut sfx: ResMut<Sfx>,
+    mut button_query: Query<(&mut Text, &mut Visibility, &Clickable, &InterventionButton)>,
+    mut budget_text_query: Query<
+        (&mut Text, &mut Visibility),
+        (With<InterventionBudgetText>, Without<InterventionButton>),
+    >,
+    mut marker_query: Query<
+        (&mut Transform2, &mut Visibility),
+        (
+            With<InterventionTargetMarker>,
+            Without<InterventionButton>,
+            Without<InterventionBudgetText>,
+        ),
+    >,
+    clickable_query: Query<&Clickable, Without<InterventionTarget>>,
+    target_query: Query<&Clickable, With<InterventionTarget>>,
+    unit_query: Query<(&Unit, &Health, &GlobalTransform)>,
+    app_state: Res<State<AppState>>,
+    replay_state: Res<ReplayState>,
+    item_definitions: Res<ItemDefinitions>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    cursor: Res<Cursor>,
+) {
+    let enabled = interventions.budget() > 0 && !replay_state.playing();
+    if !enabled {
+        intervention_controls.targeting = None;
+    }
+
+    // the sandbox can throw any spice, the campaign only what is left in the inventory
+    let mut spices: Vec<Item> = vec![];
+    if app_state.0 == AppState::Sandbox {
+        spices.extend(
+            item_definitions
+                .iter()
+                .map(|definition| definition.id.clone()),
+        );
+    } else {
+        for item in game_state.inventory.items() {
+            if !spices.contains(item) {
+                spices.push(item.clone());
+            }
+        }
+    }
+    if intervention_controls.selected_spice >= spices.len() {
+        intervention_controls.selected_spice = 0;
+    }
+    let selected_spice = spices.get(intervention_controls.selected_spice).cloned();
+
+    for (mut button_text, mut button_visibility, button_clickable, button) in
+        button_query.iter_mut()
+    {
+        *button_visibility = if enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if !enabled {
+            continue;
+        }
+        if button_clickable.just_hovered() {
+            sfx.play(SfxKind::UiButtonHover);
+        }
+        if button_clickable.confirmed {
+            sfx.play(SfxKind::UiButtonClick);
+            match button.kind {
+                InterventionButtonKind::Intervention(kind) if interventions.pending(kind) => {}
+                InterventionButtonKind::Spice => {
+                    if !spices.is_empty() {
+                        intervention_controls.selected_spice =
+                            (intervention_controls.selected_spice + 1) % spices.len();
+                    }
+                }
+                InterventionButtonKind::Intervention(InterventionKind::Rally) => {
+                    if interventions.ready(InterventionKind::Rally) {
+                        sfx.play(SfxKind::UiButtonConfirm);
+                        interventions.request(Intervention::Rally);
+                    }
+                    intervention_controls.targeting = None;
+                }
+                InterventionButtonKind::Intervention(kind) => {
+                    intervention_controls.targeting =
+                        if intervention_controls.targeting == Some(kind) {
+                            None
+                        } else {
+                            Some(kind)
+                        };
+                }
+            }
+        }
+        let (label, active) = match button.kind {
+            InterventionButtonKind::Spice => (
+                selected_spice
+                    .as_ref()
+                    .map(|item| item_definitions.name(item).to_owned())
+                    .unwrap_or_else(|| "No Spice".to_owned()),
+                false,
+            ),
+            InterventionButtonKind::Intervention(kind) => {
+                let cooldown = interventions.cooldown(kind);
+                let label = if cooldown > 0. {
+                    format!("{} ({:.0})", kind.name(), cooldown.ceil())
+                } else {
+                    kind.name().to_owned()
+                };
+                (label, intervention_controls.targeting == Some(kind))
+            }
+        };
+        let pending = match button.kind {
+            InterventionButtonKind::Intervention(kind) => interventions.pending(kind),
+            InterventionButtonKind::Spice => false,
+        };
+        if let Some(section) = button_text.sections.get_mut(0) {
+            section.value = label;
+            section.style.color = if pending {
+                Color::DARK_GRAY
+            } else if button_clickable.hovered || active {
+                Color::WHITE
+            } else {
+                Color::GRAY
+            };
+        }
+    }
+
+    for (mut budget_text, mut budget_visibility) in budget_text_query.iter_mut() {
+        *budget_visibility = if enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if let Some(section) = budget_text.sections.get_mut(0) {
+            section.value = format!("Orders left: {}", interventions.remaining());
+        }
+    }
+
+    if mouse_buttons.just_pressed(MouseButton::Right) || keys.just_pressed(KeyCode::Escape) {
+        intervention_controls.targeting = None;
+    }
+    let over_button = clickable_query.iter().any(|clickable| clickable.hovered);
+    let target_confirmed = !over_button && target_query.iter().any(|clickable| clickable.confirmed);
+    if target_confirmed {
+        match intervention_controls.targeting {
+            Some(InterventionKind::ThrowSpice) => {
+                if let Some(item) = selected_spice {
+                    if interventions.ready(InterventionKind::ThrowSpice)
+                        && !interventions.pending(InterventionKind::ThrowSpice)
+                    {
+                        sfx.play(SfxKind::UiButtonConfirm);
+                        interventions.request(Intervention::ThrowSpice {
+                            x: cursor.position.x,
+                            y: cursor.position.y,
+                            modifiers: item_definitions.modifiers(&item),
+                        });
+                        if app_state.0 != AppState::Sandbox {
+                            intervention_controls.thrown_spice = Some(item);
+                        }
+                    }
+                }
+                intervention_controls.targeting = None;
+            }
+            Some(InterventionKind::RetreatHorn) => {
+                let nearest_unit = unit_query
+                    .iter()
+                    .filter(|(unit, health, _)| unit.team == Team::Friendly && health.current > 0.)
+                    .map(|(unit, _, transform)| {
+                        (
+                            unit.kind,
+                            transform.translation().truncate().distance(cursor.position),
+                        )
+                    })
+                    .filter(|(_, distance)| *distance < RETREAT_HORN_PICK_DISTANCE)
+                    .min_by(|(_, a), (_, b)| a.total_cmp(b));
+                if let Some((unit_kind, _)) = nearest_unit {
+                    if interventions.ready(InterventionKind::RetreatHorn)
+                        && !interventions.pending(InterventionKind::RetreatHorn)
+                    {
+                        sfx.play(SfxKind::UiButtonConfirm);
+                        interventions.request(Intervention::RetreatHorn { unit_kind });
+                    }
+                    intervention_controls.targeting = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (mut marker_transform, mut marker_visibility) in marker_query.iter_mut() {
+        marker_transform.translation = cursor.position;
+        *marker_visibility =
+            if intervention_controls.targeting == Some(InterventionKind::ThrowSpice) {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+    }
+}
+
+fn intervention_controls_consume_spice(
+    mut intervention_controls: ResMut<InterventionControls>,
+    mut intervention_events: EventReader<InterventionEvent>,
+    mut game_state: ResMut<GameState>,
+    interventions: Res<Interventions>,
+) {
+    let thrown = intervention_events
+        .iter()
+        .filter(|intervention_event| {
+            intervention_event.intervention.kind() == InterventionKind::ThrowSpice
+        })
+        .count()
+        > 0;
+    if thrown {
+        if let Some(item) = intervention_controls.thrown_spice.take() {
+            game_state.inventory.remove_last(&item);
+            game_state.used_items.push(item);
+        }
+    } else if !interventions.pending(InterventionKind::ThrowSpice) {
+        // the request was dropped, so the spice stays in the inventory
+        intervention_controls.thrown_spice = None;
+    }
+}
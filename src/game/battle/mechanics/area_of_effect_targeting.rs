@@ -1,72 +1,133 @@
-use std::collections::HashMap;
-
 use bevy::prelude::*;
 
-use crate::{DamageReceiveEvent, EventSet, Team, UpdateSet};
+use crate::{
+    Broadphase, CollisionShape, DamageFlags, DebugDraw, DebugDrawSettings, DebugRectangle,
+    HurtBoxTimeline, Team, TranslatedCollisionShape,
+};
+
+const AREA_OF_EFFECT_MAX_CANDIDATES: usize = 24;
+const EXPECTED_HIT_PIP_SIZE: f32 = 16.;
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
 pub enum AreaOfEffectTargetingSystem {
-    Update,
+    DebugDraw,
 }
 
 pub struct AreaOfEffectTargetingPlugin;
 
 impl Plugin for AreaOfEffectTargetingPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<AreaOfEffectTargeting>().add_system(
-            area_of_effect_targeting_update
-                .in_schedule(CoreSchedule::FixedUpdate)
-                .in_set(AreaOfEffectTargetingSystem::Update)
-                .in_set(UpdateSet)
-                .after(EventSet::<DamageReceiveEvent>::Sender),
+        app.add_system(
+            area_of_effect_targeting_debug_draw
+                .in_set(AreaOfEffectTargetingSystem::DebugDraw)
+                .run_if(resource_exists::<DebugDrawSettings>()),
         );
     }
 }
 
-#[derive(Resource, Default)]
-pub struct AreaOfEffectTargeting {
-    targets: HashMap<Team, Vec2>,
-}
-
 #[derive(Component)]
 pub struct Target {
     pub team: Team,
 }
 
-impl AreaOfEffectTargeting {
-    pub fn get_target(&self, target_team: Team) -> Option<Vec2> {
-        if let Some(target) = self.targets.get(&target_team) {
-            Some(*target)
-        } else {
-            None
+#[derive(Clone, Copy, Component)]
+pub struct AreaOfEffectTarget {
+    pub position: Vec2,
+    pub expected_hits: usize,
+}
+
+pub fn expected_hits(
+    broadphase: &Broadphase,
+    flags: DamageFlags,
+    area: TranslatedCollisionShape,
+) -> usize {
+    broadphase
+        .overlapping(area)
+        .filter(|entry| entry.flags.intersects(flags))
+        .count()
+}
+
+// tries hostile hit boxes in range as a centre and keeps whichever catches the most
+pub fn area_of_effect_target(
+    broadphase: &Broadphase,
+    flags: DamageFlags,
+    range: TranslatedCollisionShape,
+    shape: CollisionShape,
+) -> Option<AreaOfEffectTarget> {
+    let mut candidates = broadphase
+        .overlapping(range)
+        .filter(|entry| entry.flags.intersects(flags))
+        .collect::<Vec<_>>();
+    // big crowds only try an even spread of centres along the line to keep the cost per cast bounded
+    candidates.sort_by(|a, b| a.shape.translation.x.total_cmp(&b.shape.translation.x));
+    let step = candidates.len() / AREA_OF_EFFECT_MAX_CANDIDATES + 1;
+    let mut best: Option<AreaOfEffectTarget> = None;
+    for candidate in candidates.into_iter().step_by(step) {
+        let position = candidate.shape.translation;
+        let (sum, count) = broadphase
+            .overlapping(shape.at(position))
+            .filter(|entry| entry.flags.intersects(flags))
+            .fold((Vec2::ZERO, 0), |(sum, count), entry| {
+                (sum + entry.shape.translation, count + 1)
+            });
+        let mut target = AreaOfEffectTarget {
+            position,
+            expected_hits: count,
+        };
+        // centring on what it would catch often fits more of the group in, if that is still in range
+        let centre = sum / count.max(1) as f32;
+        if count > 1 && range.overlaps(shape.at(centre)) {
+            let centre_hits = expected_hits(broadphase, flags, shape.at(centre));
+            if centre_hits >= count {
+                target = AreaOfEffectTarget {
+                    position: centre,
+                    expected_hits: centre_hits,
+                };
+            }
+        }
+        if best
+            .map(|best| target.expected_hits > best.expected_hits)
+            .unwrap_or(true)
+        {
+            best = Some(target);
         }
     }
+    best
 }
 
-pub fn area_of_effect_targeting_update(
-    mut area_of_effect_targeting: ResMut<AreaOfEffectTargeting>,
-    target_query: Query<(&Target, &GlobalTransform)>,
+pub fn area_of_effect_targeting_debug_draw(
+    mut debug_draw: ResMut<DebugDraw>,
+    debug_draw_settings: Res<DebugDrawSettings>,
+    target_query: Query<(&AreaOfEffectTarget, &HurtBoxTimeline)>,
 ) {
-    let mut positions_map: HashMap<Team, Vec<Vec2>> = HashMap::new();
-    for (target, target_transform) in target_query.iter() {
-        let position = target_transform.translation().truncate();
-        if position.x * -target.team.move_direction() < 500. {
-            if let Some(positions) = positions_map.get_mut(&target.team) {
-                positions.push(position);
-            } else {
-                positions_map.insert(target.team, vec![position]);
-            }
-        }
+    if !debug_draw_settings.draw_area_of_effect_targets {
+        return;
     }
-    area_of_effect_targeting.targets = HashMap::new();
-    for (team, positions) in positions_map.iter() {
-        let mut target_position = Vec2::ZERO;
-        for position in positions.iter() {
-            target_position += *position;
+    for (target, hurt_box_timeline) in target_query.iter() {
+        let CollisionShape::Rect { offset, size } = hurt_box_timeline.hurt_box.shape else {
+            continue;
+        };
+        let position = target.position + offset;
+        debug_draw.draw(DebugRectangle {
+            position,
+            size,
+            color: Color::rgba(1., 0.6, 0., 0.15),
+            ..Default::default()
+        });
+        // one pip per hit box the caster expected to catch
+        for i in 0..target.expected_hits {
+            debug_draw.draw(DebugRectangle {
+                position: position
+                    + Vec2::new(
+                        (i as f32 - (target.expected_hits - 1) as f32 * 0.5)
+                            * EXPECTED_HIT_PIP_SIZE
+                            * 1.5,
+                        size.y * 0.5 + EXPECTED_HIT_PIP_SIZE,
+                    ),
+                size: Vec2::splat(EXPECTED_HIT_PIP_SIZE),
+                color: Color::rgba(1., 0.6, 0., 0.8),
+                ..Default::default()
+            });
         }
-        target_position /= positions.len() as f32;
-        area_of_effect_targeting
-            .targets
-            .insert(*team, target_position);
     }
 }
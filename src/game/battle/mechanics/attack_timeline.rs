@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{DamageSystem, HurtBox, UpdateSet};
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum AttackTimelineSystem {
+    Update,
+}
+
+pub struct AttackTimelinePlugin;
+
+impl Plugin for AttackTimelinePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            hurt_box_timeline_update
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(AttackTimelineSystem::Update)
+                .in_set(UpdateSet)
+                .before(DamageSystem::Update),
+        );
+    }
+}
+
+// mirrors the timing of an attack animation so battles play out the same without spine
+#[derive(Clone, Deserialize)]
+pub struct AttackTimeline {
+    pub duration: f32,
+    pub hits: Vec<f32>,
+}
+
+impl AttackTimeline {
+    pub fn advance(&self, time: &mut f32, delta: f32) -> usize {
+        let mut hits = 0;
+        let mut from = *time;
+        let mut to = *time + delta;
+        loop {
+            hits += self
+                .hits
+                .iter()
+                .filter(|hit| **hit >= from && **hit < to)
+                .count();
+            if to < self.duration || self.duration <= 0. {
+                break;
+            }
+            from = 0.;
+            to -= self.duration;
+        }
+        *time = to;
+        hits
+    }
+}
+
+#[derive(Component)]
+pub struct HurtBoxTimeline {
+    pub hurt_box: HurtBox,
+    pub damage_start: f32,
+    pub damage_end: f32,
+    pub duration: f32,
+    pub time: f32,
+}
+
+impl HurtBoxTimeline {
+    pub fn new(hurt_box: HurtBox, damage_start: f32, damage_end: f32, duration: f32) -> Self {
+        Self {
+            hurt_box,
+            damage_start,
+            damage_end,
+            duration,
+            time: 0.,
+        }
+    }
+}
+
+pub fn hurt_box_timeline_update(
+    mut hurt_box_timeline_query: Query<(Entity, &mut HurtBoxTimeline)>,
+    mut commands: Commands,
+    time: Res<FixedTime>,
+) {
+    for (hurt_box_timeline_entity, mut hurt_box_timeline) in hurt_box_timeline_query.iter_mut() {
+        let previous_time = hurt_box_timeline.time;
+        hurt_box_timeline.time += time.period.as_secs_f32();
+        let Some(mut entity_commands) = commands.get_entity(hurt_box_timeline_entity) else {
+            continue;
+        };
+        if hurt_box_timeline.time >= hurt_box_timeline.duration {
+            entity_commands.despawn_recursive();
+        } else if previous_time < hurt_box_timeline.damage_end
+            && hurt_box_timeline.time >= hurt_box_timeline.damage_end
+        {
+            entity_commands.remove::<HurtBox>();
+        } else if previous_time < hurt_box_timeline.damage_start
+            && hurt_box_timeline.time >= hurt_box_timeline.damage_start
+        {
+            entity_commands.insert(hurt_box_timeline.hurt_box);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{DamageFlags, HitBox, TranslatedCollisionShape, UpdateSet};
+
+const CELL_SIZE: f32 = 400.;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum BroadphaseSystem {
+    Update,
+}
+
+pub struct BroadphasePlugin;
+
+impl Plugin for BroadphasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Broadphase>().add_system(
+            broadphase_update
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(BroadphaseSystem::Update)
+                .in_set(UpdateSet),
+        );
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct BroadphaseEntry {
+    pub entity: Entity,
+    pub flags: DamageFlags,
+    pub shape: TranslatedCollisionShape,
+    min_cell: IVec2,
+}
+
+#[derive(Resource, Default)]
+pub struct Broadphase {
+    entries: Vec<BroadphaseEntry>,
+    cells: HashMap<IVec2, Vec<BroadphaseEntry>>,
+    min_cell: IVec2,
+    max_cell: IVec2,
+}
+
+impl Broadphase {
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.cells.clear();
+        self.min_cell = IVec2::splat(i32::MAX);
+        self.max_cell = IVec2::splat(i32::MIN);
+    }
+
+    pub fn insert(&mut self, entity: Entity, flags: DamageFlags, shape: TranslatedCollisionShape) {
+        let Some((min, max)) = shape.bounds() else {
+            return;
+        };
+        let (min_cell, max_cell) = (cell(min), cell(max));
+        let entry = BroadphaseEntry {
+            entity,
+            flags,
+            shape,
+            min_cell,
+        };
+        self.entries.push(entry);
+        self.min_cell = self.min_cell.min(min_cell);
+        self.max_cell = self.max_cell.max(max_cell);
+        for x in min_cell.x..=max_cell.x {
+            for y in min_cell.y..=max_cell.y {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(entry);
+            }
+        }
+    }
+
+    pub fn entries(&self) -> &Vec<BroadphaseEntry> {
+        &self.entries
+    }
+
+    pub fn query_region(
+        &self,
+        min: Vec2,
+        max: Vec2,
+    ) -> impl Iterator<Item = &BroadphaseEntry> + '_ {
+        let min_cell = cell(min).max(self.min_cell);
+        let max_cell = cell(max).min(self.max_cell);
+        (min_cell.x..=max_cell.x)
+            .flat_map(move |x| (min_cell.y..=max_cell.y).map(move |y| IVec2::new(x, y)))
+            .flat_map(move |region_cell| {
+                self.cells
+                    .get(&region_cell)
+                    .into_iter()
+                    .flatten()
+                    // entries spanning several cells are only visited from the first one in the region
+                    .filter(move |entry| entry.min_cell.max(min_cell) == region_cell)
+            })
+    }
+
+    pub fn overlapping(
+        &self,
+        shape: TranslatedCollisionShape,
+    ) -> impl Iterator<Item = &BroadphaseEntry> + '_ {
+        shape
+            .bounds()
+            .into_iter()
+            .flat_map(|(min, max)| self.query_region(min, max))
+            .filter(move |entry| shape.overlaps(entry.shape))
+    }
+}
+
+fn cell(position: Vec2) -> IVec2 {
+    (position / CELL_SIZE).floor().as_ivec2()
+}
+
+pub fn broadphase_update(
+    mut broadphase: ResMut<Broadphase>,
+    hit_box_query: Query<(Entity, &HitBox, &GlobalTransform)>,
+) {
+    broadphase.clear();
+    for (hit_box_entity, hit_box, hit_box_transform) in hit_box_query.iter() {
+        broadphase.insert(
+            hit_box_entity,
+            hit_box.flags,
+            hit_box.shape.at(hit_box_transform.translation().truncate()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CollisionShape;
+
+    fn rect(translation: Vec2, size: Vec2) -> TranslatedCollisionShape {
+        CollisionShape::Rect {
+            offset: Vec2::ZERO,
+            size,
+        }
+        .at(translation)
+    }
+
+    #[test]
+    fn query_region_visits_entries_spanning_several_cells_once() {
+        let mut broadphase = Broadphase::default();
+        broadphase.clear();
+        let wide = Entity::from_raw(0);
+        let small = Entity::from_raw(1);
+        // spans a 3x3 block of cells
+        broadphase.insert(
+            wide,
+            DamageFlags::all(),
+            rect(Vec2::ZERO, Vec2::splat(CELL_SIZE * 2.)),
+        );
+        broadphase.insert(
+            small,
+            DamageFlags::all(),
+            rect(Vec2::splat(CELL_SIZE * 0.5), Vec2::splat(10.)),
+        );
+        for (min, max) in [
+            (Vec2::splat(-CELL_SIZE * 2.), Vec2::splat(CELL_SIZE * 2.)),
+            (Vec2::ZERO, Vec2::splat(CELL_SIZE * 2.)),
+            (Vec2::splat(CELL_SIZE * 0.25), Vec2::splat(CELL_SIZE * 0.75)),
+        ] {
+            let entities = broadphase
+                .query_region(min, max)
+                .map(|entry| entry.entity)
+                .collect::<Vec<_>>();
+            assert_eq!(entities.iter().filter(|entity| **entity == wide).count(), 1);
+            assert_eq!(
+                entities.iter().filter(|entity| **entity == small).count(),
+                1
+            );
+        }
+        let overlapping = broadphase
+            .overlapping(rect(Vec2::splat(-CELL_SIZE * 0.9), Vec2::splat(10.)))
+            .map(|entry| entry.entity)
+            .collect::<Vec<_>>();
+        assert_eq!(overlapping, vec![wide]);
+    }
+}
@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+
+use crate::{
+    BattleState, DamageReceiveEvent, EventSet, Transform2, UpdateSet, SPAWN_Y_MAX, SPAWN_Y_MIN,
+};
+
+// the battlefield is seen from the side, so units only need a thin slice of it in depth
+const CROWD_DEPTH_SCALE: f32 = 0.2;
+const CROWD_PUSH_STRENGTH: f32 = 8.;
+const CROWD_KNOCKBACK_DAMPING: f32 = 6.;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum CrowdSystem {
+    Update,
+}
+
+pub struct CrowdPlugin;
+
+impl Plugin for CrowdPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            crowd_update
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(CrowdSystem::Update)
+                .in_set(UpdateSet)
+                .after(EventSet::<DamageReceiveEvent>::Sender),
+        );
+    }
+}
+
+#[derive(Default, Component)]
+pub struct Crowd {
+    pub mass: f32,
+    pub radius: f32,
+    pub velocity: Vec2,
+}
+
+struct CrowdMember {
+    index: usize,
+    position: Vec2,
+    mass: f32,
+    radius: f32,
+}
+
+pub fn crowd_update(
+    mut crowd_query: Query<(&mut Crowd, &mut Transform2)>,
+    mut damage_receive_events: EventReader<DamageReceiveEvent>,
+    battle_state: Res<BattleState>,
+    time: Res<FixedTime>,
+) {
+    if !battle_state.battling() {
+        damage_receive_events.clear();
+        return;
+    }
+    let delta_seconds = time.period.as_secs_f32();
+    for damage_receive_event in damage_receive_events.iter() {
+        if let Ok((mut crowd, _)) = crowd_query.get_mut(damage_receive_event.entity) {
+            let mass = crowd.mass.max(1.);
+            crowd.velocity += damage_receive_event.knockback / mass;
+        }
+    }
+
+    let mut members = crowd_query
+        .iter()
+        .enumerate()
+        .map(|(index, (crowd, transform))| CrowdMember {
+            index,
+            position: transform.translation,
+            mass: crowd.mass.max(1.),
+            radius: crowd.radius,
+        })
+        .collect::<Vec<_>>();
+    let max_radius = members
+        .iter()
+        .map(|member| member.radius)
+        .fold(0., f32::max);
+    // sorted along x so each unit only looks ahead until nobody else can reach it
+    members.sort_by(|a, b| a.position.x.total_cmp(&b.position.x));
+    let mut pushes = vec![Vec2::ZERO; members.len()];
+    for (i, member) in members.iter().enumerate() {
+        for other in members[i + 1..].iter() {
+            if other.position.x - member.position.x > member.radius + max_radius {
+                break;
+            }
+            let reach = member.radius + other.radius;
+            let reach = Vec2::new(reach, reach * CROWD_DEPTH_SCALE);
+            let scaled_offset = (other.position - member.position) / reach;
+            let distance = scaled_offset.length();
+            if distance >= 1. {
+                continue;
+            }
+            // units on the exact same spot are split apart along the lane
+            let direction = if distance > 0. {
+                scaled_offset / distance
+            } else {
+                Vec2::X
+            };
+            let push = direction * reach * (1. - distance) * CROWD_PUSH_STRENGTH * delta_seconds;
+            // heavier units barely budge and shove lighter ones aside instead
+            let total_mass = member.mass + other.mass;
+            pushes[member.index] -= push * (other.mass / total_mass);
+            pushes[other.index] += push * (member.mass / total_mass);
+        }
+    }
+
+    for (index, (mut crowd, mut transform)) in crowd_query.iter_mut().enumerate() {
+        let velocity = crowd.velocity;
+        transform.translation += pushes[index] + velocity * delta_seconds;
+        transform.translation.y = transform.translation.y.clamp(SPAWN_Y_MIN, SPAWN_Y_MAX);
+        crowd.velocity *= (1. - CROWD_KNOCKBACK_DAMPING * delta_seconds).max(0.);
+    }
+}
@@ -2,12 +2,13 @@ use bevy::prelude::*;
 use bitflags::bitflags;
 use enum_map::{Enum, EnumMap};
 use rand::prelude::*;
+use serde::Deserialize;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 use crate::{
-    AddFixedEvent, CollisionShape, DebugDraw, DebugDrawSettings, DebugRectangle, EventSet,
-    FramesToLiveSystem, UpdateSet,
+    AddFixedEvent, BattleRng, Broadphase, CollisionShape, DebugDraw, DebugDrawSettings,
+    DebugRectangle, EventSet, FramesToLiveSystem, Projectile, Team, UnitKind, UpdateSet,
 };
 
 bitflags! {
@@ -23,7 +24,7 @@ impl Default for DamageFlags {
     }
 }
 
-#[derive(Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Enum, EnumIter, Deserialize)]
 pub enum DamageKind {
     #[default]
     Flesh,
@@ -32,6 +33,32 @@ pub enum DamageKind {
     Magic,
 }
 
+impl DamageKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Flesh => "Flesh",
+            Self::Sword => "Sword",
+            Self::Arrow => "Arrow",
+            Self::Magic => "Magic",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DamageCause {
+    Attack,
+    Combustion,
+    Explosion,
+    Poison,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DamageSource {
+    pub team: Team,
+    pub kind: UnitKind,
+    pub cause: DamageCause,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Enum, EnumIter)]
 pub enum DamageModifier {
     Fire,
@@ -90,7 +117,7 @@ impl DamageKind {
     }
 }
 
-#[derive(Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Deserialize)]
 pub enum DefenseKind {
     #[default]
     Flesh,
@@ -135,7 +162,11 @@ impl Plugin for DamagePlugin {
                     .in_set(EventSet::<DamageReceiveEvent>::Sender)
                     .after(EventSet::<DamageInflictEvent>::Sender),
             )
-            .add_system(damage_debug_draw.in_set(DamageSystem::DebugDraw));
+            .add_system(
+                damage_debug_draw
+                    .in_set(DamageSystem::DebugDraw)
+                    .run_if(resource_exists::<DebugDrawSettings>()),
+            );
     }
 }
 
@@ -158,6 +189,8 @@ pub struct HurtBox {
     pub max_hits: usize,
     pub ignore_entity: Entity,
     pub slow: bool,
+    pub knockback: f32,
+    pub source: DamageSource,
 }
 
 #[derive(Clone, Copy, Default, Component)]
@@ -166,13 +199,26 @@ pub struct HurtBoxDespawner;
 pub struct DamageInflictEvent {
     pub entity: Entity,
     pub damage: f32,
+    pub damage_kind: DamageKind,
+    pub damage_modifiers: DamageModifiers,
     pub slow: bool,
+    pub resisted: bool,
+    // where a projectile struck, if the damage came from one
+    pub contact: Option<Vec2>,
+    pub knockback: Vec2,
+    pub source: DamageSource,
 }
 
 pub struct DamageReceiveEvent {
     pub entity: Entity,
     pub damage: f32,
+    pub damage_kind: DamageKind,
+    pub damage_modifiers: DamageModifiers,
     pub slow: bool,
+    pub resisted: bool,
+    pub contact: Option<Vec2>,
+    pub knockback: Vec2,
+    pub source: DamageSource,
     _private: (),
 }
 
@@ -180,64 +226,118 @@ pub struct DamageCandidate {
     entity: Entity,
     damage: f32,
     slow: bool,
+    resisted: bool,
+    contact: Option<Vec2>,
+    knockback: Vec2,
+    impact_time: f32,
 }
 
 pub fn damage_update(
     mut damage_inflict_events: EventWriter<DamageInflictEvent>,
-    mut hurt_box_query: Query<(Entity, &mut HurtBox, Option<&HurtBoxDespawner>)>,
+    mut hurt_box_query: Query<(
+        Entity,
+        &mut HurtBox,
+        Option<&HurtBoxDespawner>,
+        Option<&Projectile>,
+    )>,
     mut commands: Commands,
-    hit_box_query: Query<(Entity, &HitBox)>,
+    hit_box_query: Query<&HitBox>,
     transform_query: Query<&GlobalTransform>,
+    broadphase: Res<Broadphase>,
+    mut battle_rng: ResMut<BattleRng>,
 ) {
-    for (hurt_box_entity, mut hurt_box, hurt_box_despawner) in hurt_box_query.iter_mut() {
+    for (hurt_box_entity, mut hurt_box, hurt_box_despawner, projectile) in hurt_box_query.iter_mut()
+    {
         let Ok(hurt_box_transform) = transform_query.get(hurt_box_entity) else {
             continue;
         };
         if hurt_box.max_hits == 0 {
             continue;
         }
+        let position = hurt_box_transform.translation().truncate();
+        // projectiles test the whole path they moved along so fast ones can't skip past a hit box
+        let previous_position = projectile
+            .and_then(|projectile| projectile.previous_translation)
+            .unwrap_or(position);
+        let delta = position - previous_position;
+        let start_shape = hurt_box.shape.at(previous_position);
+        let Some((region_min, region_max)) = start_shape
+            .bounds()
+            .zip(hurt_box.shape.at(position).bounds())
+            .map(|((start_min, start_max), (end_min, end_max))| {
+                (start_min.min(end_min), start_max.max(end_max))
+            })
+        else {
+            continue;
+        };
+        // heavy hits shove targets away from the attacker, or from the blast if there is none
+        let knockback_origin = transform_query
+            .get(hurt_box.ignore_entity)
+            .map(|transform| transform.translation().truncate())
+            .unwrap_or(position);
         let mut damage_candidates = vec![];
-        for (hit_box_entity, hit_box) in hit_box_query.iter() {
-            if hurt_box_entity == hit_box_entity {
+        for entry in broadphase.query_region(region_min, region_max) {
+            if hurt_box_entity == entry.entity {
+                continue;
+            }
+            if hurt_box.ignore_entity == entry.entity {
                 continue;
             }
-            if hurt_box.ignore_entity == hit_box_entity {
+            if hurt_box.flags & entry.flags == DamageFlags::empty() {
                 continue;
             }
-            let Ok(hit_box_transform) = transform_query.get(hit_box_entity) else {
+            let Some(impact_time) = start_shape.sweep(delta, entry.shape) else {
                 continue;
             };
-            if hurt_box
-                .shape
-                .at(hurt_box_transform.translation().truncate())
-                .overlaps(hit_box.shape.at(hit_box_transform.translation().truncate()))
-                && hurt_box.flags & hit_box.flags != DamageFlags::empty()
-            {
-                let mut damage =
-                    hurt_box.damage * hurt_box.damage_kind.damage_multiplier(hit_box.defense_kind);
-                for damage_modifier in DamageModifier::iter() {
-                    if hurt_box.damage_modifiers[damage_modifier] {
-                        damage *= damage_modifier
-                            .damage_multiplier(hit_box.defense_kind, hit_box.defense_modifiers);
-                    }
-                }
-                damage /= hit_box.defense;
-                if damage > 0. {
-                    damage_candidates.push(DamageCandidate {
-                        entity: hit_box_entity,
-                        damage,
-                        slow: hurt_box.slow,
-                    });
+            let Ok(hit_box) = hit_box_query.get(entry.entity) else {
+                continue;
+            };
+            let mut damage =
+                hurt_box.damage * hurt_box.damage_kind.damage_multiplier(hit_box.defense_kind);
+            let mut resisted = false;
+            for damage_modifier in DamageModifier::iter() {
+                if hurt_box.damage_modifiers[damage_modifier] {
+                    let damage_multiplier = damage_modifier
+                        .damage_multiplier(hit_box.defense_kind, hit_box.defense_modifiers);
+                    resisted |= damage_multiplier < 1.;
+                    damage *= damage_multiplier;
                 }
             }
+            damage /= hit_box.defense;
+            if damage > 0. {
+                let contact = projectile.and_then(|_| {
+                    let (entry_min, entry_max) = entry.shape.bounds()?;
+                    let (start_min, start_max) = start_shape.bounds()?;
+                    let centre = (start_min + start_max) * 0.5 + delta * impact_time;
+                    Some(centre.clamp(entry_min, entry_max))
+                });
+                damage_candidates.push(DamageCandidate {
+                    entity: entry.entity,
+                    damage,
+                    slow: hurt_box.slow,
+                    resisted,
+                    contact,
+                    knockback: (entry.shape.translation - knockback_origin).normalize_or_zero()
+                        * hurt_box.knockback,
+                    impact_time,
+                });
+            }
         }
-        damage_candidates.shuffle(&mut thread_rng());
+        damage_candidates.shuffle(&mut *battle_rng);
+        // the first hit box along the path takes the hit, the rest stay shuffled
+        damage_candidates.sort_by(|a, b| a.impact_time.total_cmp(&b.impact_time));
         for damage_candidate in damage_candidates {
             if hurt_box.max_hits > 0 {
                 damage_inflict_events.send(DamageInflictEvent {
                     entity: damage_candidate.entity,
                     damage: damage_candidate.damage,
+                    damage_kind: hurt_box.damage_kind,
+                    damage_modifiers: hurt_box.damage_modifiers,
                     slow: damage_candidate.slow,
+                    resisted: damage_candidate.resisted,
+                    contact: damage_candidate.contact,
+                    knockback: damage_candidate.knockback,
+                    source: hurt_box.source,
                 });
                 hurt_box.max_hits -= 1;
             } else {
@@ -260,7 +360,13 @@ pub fn damage_events(
         damage_receive_events.send(DamageReceiveEvent {
             entity: damage_inflict_event.entity,
             damage: damage_inflict_event.damage,
+            damage_kind: damage_inflict_event.damage_kind,
+            damage_modifiers: damage_inflict_event.damage_modifiers,
             slow: damage_inflict_event.slow,
+            resisted: damage_inflict_event.resisted,
+            contact: damage_inflict_event.contact,
+            knockback: damage_inflict_event.knockback,
+            source: damage_inflict_event.source,
             _private: (),
         });
     }
@@ -1,8 +1,8 @@
 use bevy::prelude::*;
 
 use crate::{
-    CollisionShape, DamageFlags, DamageReceiveEvent, DebugDraw, DebugDrawSettings, DebugRectangle,
-    EventSet, HitBox, UpdateSet,
+    Broadphase, CollisionShape, DamageFlags, DamageReceiveEvent, DebugDraw, DebugDrawSettings,
+    DebugRectangle, EventSet, UpdateSet,
 };
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
@@ -22,7 +22,11 @@ impl Plugin for FeelerPlugin {
                 .in_set(UpdateSet)
                 .after(EventSet::<DamageReceiveEvent>::Sender),
         )
-        .add_system(feeler_debug_draw.in_set(FeelerSystem::DebugDraw));
+        .add_system(
+            feeler_debug_draw
+                .in_set(FeelerSystem::DebugDraw)
+                .run_if(resource_exists::<DebugDrawSettings>()),
+        );
     }
 }
 
@@ -34,32 +38,16 @@ pub struct Feeler {
 }
 
 pub fn feeler_update(
-    mut feeler_query: Query<(Entity, &mut Feeler)>,
-    hit_box_query: Query<(Entity, &HitBox)>,
-    transform_query: Query<&GlobalTransform>,
+    mut feeler_query: Query<(Entity, &mut Feeler, &GlobalTransform)>,
+    broadphase: Res<Broadphase>,
 ) {
-    for (feeler_entity, mut feeler) in feeler_query.iter_mut() {
-        feeler.feeling = false;
-        let Ok(feeler_transform) = transform_query.get(feeler_entity) else {
-            continue;
-        };
-        for (hit_box_entity, hit_box) in hit_box_query.iter() {
-            if feeler_entity == hit_box_entity {
-                continue;
-            }
-            let Ok(hit_box_transform) = transform_query.get(hit_box_entity) else {
-                continue;
-            };
-            if hit_box.flags & feeler.flags != DamageFlags::empty()
-                && hit_box
-                    .shape
-                    .at(hit_box_transform.translation().truncate())
-                    .overlaps(feeler.shape.at(feeler_transform.translation().truncate()))
-            {
-                feeler.feeling = true;
-                break;
-            }
-        }
+    for (feeler_entity, mut feeler, feeler_transform) in feeler_query.iter_mut() {
+        let feeler_flags = feeler.flags;
+        feeler.feeling = broadphase
+            .overlapping(feeler.shape.at(feeler_transform.translation().truncate()))
+            .any(|entry| {
+                entry.entity != feeler_entity && entry.flags & feeler_flags != DamageFlags::empty()
+            });
     }
 }
 
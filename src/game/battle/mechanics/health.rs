@@ -16,6 +16,7 @@ impl Plugin for HealthPlugin {
                 .in_schedule(CoreSchedule::FixedUpdate)
                 .in_set(HealthSystem::ReceiveDamage)
                 .in_set(UpdateSet)
+                .in_set(EventSet::<HealthDieEvent>::Sender)
                 .after(EventSet::<DamageReceiveEvent>::Sender),
         );
     }
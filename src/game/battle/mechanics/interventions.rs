@@ -0,0 +1,248 @@
+use bevy::prelude::*;
+use enum_map::{Enum, EnumMap};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::{
+    AddFixedEvent, BattleModifier, BattleModifiers, BattlePhase, BattleRng, BattleStartEvent,
+    BattleState, DamageCause, EventSet, Health, HitBox, Morale, StatusEffect, StatusEffectKind,
+    StatusEffects, Team, Unit, UnitDefinitions, UnitKind, UpdateSet, MORALE_RALLY,
+};
+
+pub const SPICE_RADIUS: f32 = 250.;
+const RETREAT_HORN_DURATION: f32 = 4.;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum InterventionSystem {
+    Start,
+    Update,
+}
+
+pub struct InterventionsPlugin;
+
+impl Plugin for InterventionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Interventions>()
+            .add_fixed_event::<InterventionEvent>()
+            .add_system(
+                interventions_start
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(InterventionSystem::Start)
+                    .after(EventSet::<BattleStartEvent>::Sender),
+            )
+            .add_system(
+                interventions_update
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(InterventionSystem::Update)
+                    .in_set(UpdateSet)
+                    .in_set(EventSet::<InterventionEvent>::Sender),
+            );
+    }
+}
+
+// positions are stored as plain floats so interventions can be saved in replays
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub enum Intervention {
+    ThrowSpice {
+        x: f32,
+        y: f32,
+        modifiers: Vec<BattleModifier>,
+    },
+    RetreatHorn {
+        unit_kind: UnitKind,
+    },
+    Rally,
+}
+
+impl Intervention {
+    pub fn kind(&self) -> InterventionKind {
+        match self {
+            Self::ThrowSpice { .. } => InterventionKind::ThrowSpice,
+            Self::RetreatHorn { .. } => InterventionKind::RetreatHorn,
+            Self::Rally => InterventionKind::Rally,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Enum, EnumIter)]
+pub enum InterventionKind {
+    ThrowSpice,
+    RetreatHorn,
+    Rally,
+}
+
+impl InterventionKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ThrowSpice => "Throw Spice",
+            Self::RetreatHorn => "Retreat Horn",
+            Self::Rally => "Rally",
+        }
+    }
+
+    pub fn cooldown(&self) -> f32 {
+        match self {
+            Self::ThrowSpice => 8.,
+            Self::RetreatHorn => 6.,
+            Self::Rally => 15.,
+        }
+    }
+}
+
+pub struct InterventionEvent {
+    pub intervention: Intervention,
+}
+
+#[derive(Resource, Default)]
+pub struct Interventions {
+    budget: usize,
+    used: usize,
+    cooldowns: EnumMap<InterventionKind, f32>,
+    requests: Vec<Intervention>,
+}
+
+impl Interventions {
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.budget.saturating_sub(self.used)
+    }
+
+    pub fn cooldown(&self, kind: InterventionKind) -> f32 {
+        self.cooldowns[kind]
+    }
+
+    pub fn ready(&self, kind: InterventionKind) -> bool {
+        self.remaining() > 0 && self.cooldowns[kind] <= 0.
+    }
+
+    pub fn pending(&self, kind: InterventionKind) -> bool {
+        self.requests.iter().any(|request| request.kind() == kind)
+    }
+
+    pub fn request(&mut self, intervention: Intervention) {
+        self.requests.push(intervention);
+    }
+
+    pub fn clear_requests(&mut self) {
+        self.requests.clear();
+    }
+}
+
+fn interventions_start(
+    mut start_events: EventReader<BattleStartEvent>,
+    mut interventions: ResMut<Interventions>,
+) {
+    for start_event in start_events.iter() {
+        *interventions = Interventions {
+            budget: start_event.config.intervention_budget,
+            ..Default::default()
+        };
+    }
+}
+
+pub fn interventions_update(
+    mut interventions: ResMut<Interventions>,
+    mut intervention_events: EventWriter<InterventionEvent>,
+    mut unit_query: Query<(
+        &mut Unit,
+        &mut BattleModifiers,
+        &mut HitBox,
+        &mut StatusEffects,
+        &mut Morale,
+        &Health,
+        &GlobalTransform,
+    )>,
+    mut battle_rng: ResMut<BattleRng>,
+    battle_state: Res<BattleState>,
+    unit_definitions: Res<UnitDefinitions>,
+    time: Res<FixedTime>,
+) {
+    if !(battle_state.battling() && battle_state.phase() == BattlePhase::Battling) {
+        interventions.clear_requests();
+        return;
+    }
+    let delta_seconds = time.period.as_secs_f32();
+    for cooldown in interventions.cooldowns.values_mut() {
+        *cooldown = (*cooldown - delta_seconds).max(0.);
+    }
+    for intervention in std::mem::take(&mut interventions.requests) {
+        let kind = intervention.kind();
+        if !interventions.ready(kind) {
+            continue;
+        }
+        interventions.used += 1;
+        interventions.cooldowns[kind] = kind.cooldown();
+        for (
+            mut unit,
+            mut modifiers,
+            mut hit_box,
+            mut status_effects,
+            mut morale,
+            health,
+            transform,
+        ) in unit_query.iter_mut()
+        {
+            if health.current <= 0. {
+                continue;
+            }
+            match &intervention {
+                Intervention::ThrowSpice {
+                    x,
+                    y,
+                    modifiers: spice_modifiers,
+                } => {
+                    let position = transform.translation().truncate();
+                    if position.distance(Vec2::new(*x, *y)) > SPICE_RADIUS {
+                        continue;
+                    }
+                    for modifier in spice_modifiers.iter() {
+                        modifiers.add_stack(*modifier);
+                    }
+                    unit.stats = unit_definitions
+                        .unit_stats(unit.kind)
+                        .clone()
+                        .with_modifiers(&modifiers);
+                    hit_box.defense = modifiers.magnitude(BattleModifier::ExtraDefense);
+                    hit_box.defense_modifiers = modifiers.defense_modifiers();
+                    morale.baseline = Morale::cowardly_baseline(
+                        unit.stats.morale,
+                        modifiers.magnitude(BattleModifier::Cowardly),
+                    );
+                    morale.current = morale.current.min(morale.baseline);
+                    let source = unit.damage_source(DamageCause::Attack);
+                    if modifiers.active(BattleModifier::Wet) {
+                        status_effects.apply(StatusEffect::new(StatusEffectKind::Soaked, source));
+                    }
+                    if spice_modifiers.contains(&BattleModifier::Blindness)
+                        && battle_rng
+                            .gen_bool(modifiers.magnitude(BattleModifier::Blindness) as f64)
+                    {
+                        status_effects.apply(StatusEffect::new(StatusEffectKind::Blinded, source));
+                    }
+                }
+                Intervention::RetreatHorn { unit_kind } => {
+                    if unit.team == Team::Friendly && unit.kind == *unit_kind {
+                        status_effects.apply(
+                            StatusEffect::new(
+                                StatusEffectKind::Panicked,
+                                unit.damage_source(DamageCause::Attack),
+                            )
+                            .with_duration(RETREAT_HORN_DURATION),
+                        );
+                    }
+                }
+                Intervention::Rally => {
+                    // morale takes care of lifting the panic once it is high enough
+                    if unit.team == Team::Friendly && morale.routed {
+                        morale.current = morale.current.max(morale.baseline.max(MORALE_RALLY));
+                    }
+                }
+            }
+        }
+        intervention_events.send(InterventionEvent { intervention });
+    }
+}
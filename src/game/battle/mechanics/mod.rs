@@ -1,15 +1,27 @@
 mod area_of_effect_targeting;
+mod attack_timeline;
+mod broadphase;
+mod crowd;
 mod damage;
 mod feeler;
 mod health;
+mod interventions;
+mod morale;
 mod projectile;
-mod spine_attack;
 mod spine_fx;
+mod status_effects;
+mod targeting;
 
 pub use area_of_effect_targeting::*;
+pub use attack_timeline::*;
+pub use broadphase::*;
+pub use crowd::*;
 pub use damage::*;
 pub use feeler::*;
 pub use health::*;
+pub use interventions::*;
+pub use morale::*;
 pub use projectile::*;
-pub use spine_attack::*;
 pub use spine_fx::*;
+pub use status_effects::*;
+pub use targeting::*;
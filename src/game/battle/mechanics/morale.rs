@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+
+use crate::{
+    AddFixedEvent, BattlePhase, BattleState, Broadphase, DamageCause, DamageFlags,
+    DamageReceiveEvent, EventSet, Feeler, Health, HealthDieEvent, StatusEffect, StatusEffectKind,
+    StatusEffects, Unit, UnitKind, UpdateSet,
+};
+
+pub const MORALE_MAX: f32 = 100.;
+pub const MORALE_ROUT: f32 = 25.;
+pub const MORALE_RALLY: f32 = 60.;
+
+// cowardice never drags a unit this close to routing, or it would break before the fight
+const COWARDLY_MORALE_MARGIN: f32 = 10.;
+
+const DAMAGE_MORALE_LOSS: f32 = 40.;
+const ALLY_DEATH_MORALE_LOSS: f32 = 15.;
+const ALLY_DEATH_RADIUS: f32 = 300.;
+const OUTNUMBERED_MORALE_LOSS: f32 = 6.;
+const BURNING_MORALE_LOSS: f32 = 10.;
+const BRUTE_MORALE_GAIN: f32 = 8.;
+const BRUTE_RADIUS: f32 = 400.;
+const BANNER_MORALE_GAIN: f32 = 8.;
+const BANNER_RADIUS: f32 = 500.;
+const MORALE_RECOVERY: f32 = 2.;
+const ROUTED_MORALE_RECOVERY: f32 = 4.;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum MoraleSystem {
+    Update,
+}
+
+pub struct MoralePlugin;
+
+impl Plugin for MoralePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_fixed_event::<MoraleEvent>().add_system(
+            morale_update
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(MoraleSystem::Update)
+                .in_set(UpdateSet)
+                .in_set(EventSet::<MoraleEvent>::Sender)
+                .after(EventSet::<DamageReceiveEvent>::Sender)
+                .after(EventSet::<HealthDieEvent>::Sender),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct Morale {
+    pub baseline: f32,
+    pub current: f32,
+    pub routed: bool,
+}
+
+impl Morale {
+    pub fn new(baseline: f32) -> Self {
+        let baseline = baseline.clamp(0., MORALE_MAX);
+        Self {
+            baseline,
+            current: baseline,
+            routed: false,
+        }
+    }
+
+    pub fn cowardly_baseline(morale: f32, cowardice: f32) -> f32 {
+        (morale * cowardice).max((MORALE_ROUT + COWARDLY_MORALE_MARGIN).min(morale))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MoraleEventKind {
+    Routed,
+    Rallied,
+}
+
+pub struct MoraleEvent {
+    pub entity: Entity,
+    pub kind: MoraleEventKind,
+}
+
+pub fn morale_update(
+    mut morale_query: Query<(
+        Entity,
+        &mut Morale,
+        &mut StatusEffects,
+        &Unit,
+        &Feeler,
+        &Health,
+        &GlobalTransform,
+    )>,
+    mut damage_receive_events: EventReader<DamageReceiveEvent>,
+    mut health_die_events: EventReader<HealthDieEvent>,
+    mut morale_events: EventWriter<MoraleEvent>,
+    broadphase: Res<Broadphase>,
+    battle_state: Res<BattleState>,
+    time: Res<FixedTime>,
+) {
+    if !(battle_state.battling() && battle_state.phase() == BattlePhase::Battling) {
+        return;
+    }
+    for damage_receive_event in damage_receive_events.iter() {
+        if let Ok((_, mut morale, _, _, _, health, _)) =
+            morale_query.get_mut(damage_receive_event.entity)
+        {
+            morale.current -= damage_receive_event.damage / health.max * DAMAGE_MORALE_LOSS;
+        }
+    }
+    let deaths = health_die_events
+        .iter()
+        .filter_map(|health_die_event| morale_query.get(health_die_event.entity).ok())
+        .map(|(_, _, _, unit, _, _, transform)| (unit.team, transform.translation().truncate()))
+        .collect::<Vec<_>>();
+    let brutes = morale_query
+        .iter()
+        .filter(|(_, _, _, unit, _, health, _)| unit.kind == UnitKind::Brute && health.current > 0.)
+        .map(|(entity, _, _, unit, _, _, transform)| {
+            (entity, unit.team, transform.translation().truncate())
+        })
+        .collect::<Vec<_>>();
+    let delta_seconds = time.period.as_secs_f32();
+    for (entity, mut morale, mut status_effects, unit, feeler, health, transform) in
+        morale_query.iter_mut()
+    {
+        if health.current <= 0. {
+            continue;
+        }
+        let position = transform.translation().truncate();
+        for (team, death_position) in deaths.iter() {
+            if *team == unit.team && death_position.distance(position) < ALLY_DEATH_RADIUS {
+                morale.current -= ALLY_DEATH_MORALE_LOSS;
+            }
+        }
+        let mut enemies = 0;
+        let mut allies = 1;
+        for entry in broadphase.overlapping(feeler.shape.at(position)) {
+            if entry.entity == entity {
+                continue;
+            }
+            if entry.flags & feeler.flags != DamageFlags::empty() {
+                enemies += 1;
+            } else if entry.flags & unit.team.hit_flags() != DamageFlags::empty() {
+                allies += 1;
+            }
+        }
+        if enemies > allies {
+            morale.current -= OUTNUMBERED_MORALE_LOSS * (enemies - allies) as f32 * delta_seconds;
+        }
+        if status_effects.has(StatusEffectKind::Burning) {
+            morale.current -= BURNING_MORALE_LOSS * delta_seconds;
+        }
+        if brutes.iter().any(|(brute_entity, team, brute_position)| {
+            *brute_entity != entity
+                && *team == unit.team
+                && brute_position.distance(position) < BRUTE_RADIUS
+        }) {
+            morale.current += BRUTE_MORALE_GAIN * delta_seconds;
+        }
+        if unit.team.banner_position().distance(position) < BANNER_RADIUS {
+            morale.current += BANNER_MORALE_GAIN * delta_seconds;
+        }
+        if morale.current < morale.baseline {
+            let recovery = if morale.routed {
+                ROUTED_MORALE_RECOVERY
+            } else {
+                MORALE_RECOVERY
+            };
+            morale.current = (morale.current + recovery * delta_seconds).min(morale.baseline);
+        }
+        morale.current = morale.current.clamp(0., MORALE_MAX);
+        if !morale.routed && morale.current < MORALE_ROUT {
+            morale.routed = true;
+            status_effects.apply(StatusEffect::new(
+                StatusEffectKind::Panicked,
+                unit.damage_source(DamageCause::Attack),
+            ));
+            morale_events.send(MoraleEvent {
+                entity,
+                kind: MoraleEventKind::Routed,
+            });
+        // timid units rally as soon as they're back to their usual selves
+        } else if morale.routed && morale.current >= MORALE_RALLY.min(morale.baseline) {
+            morale.routed = false;
+            status_effects.remove(StatusEffectKind::Panicked);
+            morale_events.send(MoraleEvent {
+                entity,
+                kind: MoraleEventKind::Rallied,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BattleConfig, BattleModifier, BattleSimulation, Team, UnitComposition};
+
+    #[test]
+    fn cowardly_mage_does_not_rout_at_spawn() {
+        let mut config = BattleConfig {
+            friendly_units: UnitComposition {
+                mages: 5,
+                ..Default::default()
+            },
+            enemy_units: UnitComposition {
+                peasants: 5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        config
+            .friendly_modifiers
+            .add_stack(BattleModifier::Cowardly);
+        let mut simulation = BattleSimulation::new(config, 0);
+        for _ in 0..10 {
+            simulation.tick();
+        }
+        let mut morale_query = simulation.world_mut().query::<(&Morale, &Unit)>();
+        let mut mages = 0;
+        for (morale, unit) in morale_query.iter(simulation.world()) {
+            if unit.team == Team::Friendly {
+                assert!(morale.baseline < unit.stats.morale);
+                assert!(!morale.routed);
+                mages += 1;
+            }
+        }
+        assert_eq!(mages, 5);
+    }
+}
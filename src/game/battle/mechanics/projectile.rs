@@ -1,6 +1,11 @@
 use bevy::prelude::*;
 
-use crate::{DamageReceiveEvent, EventSet, Transform2, UpdateSet};
+use crate::{
+    DamageReceiveEvent, EventSet, FramesToLive, HurtBox, HurtBoxDespawner, Transform2, UpdateSet,
+};
+
+pub const PROJECTILE_GRAVITY: f32 = 800.;
+const PROJECTILE_STUCK_FRAMES: usize = 240;
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
 pub enum ProjectileSystem {
@@ -24,15 +29,118 @@ impl Plugin for ProjectilePlugin {
 #[derive(Default, Component)]
 pub struct Projectile {
     pub velocity: Vec2,
+    pub gravity: f32,
+    // misses stick in the ground once they fall below this height
+    pub ground: f32,
+    pub previous_translation: Option<Vec2>,
+    // set on the tick it reaches the ground, so damage still sweeps that last stretch
+    pub landed: bool,
+}
+
+// the flatter of the two arcs that reach the offset at this speed, or the longest shot if it is out of range
+pub fn ballistic_velocity(offset: Vec2, speed: f32, gravity: f32) -> Vec2 {
+    let direction = if offset.x < 0. { -1. } else { 1. };
+    let x = offset.x.abs().max(1.);
+    let speed_squared = speed * speed;
+    let discriminant =
+        speed_squared * speed_squared - gravity * (gravity * x * x + 2. * offset.y * speed_squared);
+    let angle = if discriminant >= 0. {
+        ((speed_squared - discriminant.sqrt()) / (gravity * x)).atan()
+    } else {
+        std::f32::consts::FRAC_PI_4
+    };
+    Vec2::new(direction * angle.cos(), angle.sin()) * speed
 }
 
 pub fn projectile_update(
-    mut projectile_query: Query<(&mut Projectile, &mut Transform2)>,
+    mut projectile_query: Query<(Entity, &mut Projectile, &mut Transform2, Option<&HurtBox>)>,
+    mut commands: Commands,
     time: Res<FixedTime>,
 ) {
-    for (mut projectile, mut projectile_transform) in projectile_query.iter_mut() {
+    for (projectile_entity, mut projectile, mut projectile_transform, hurt_box) in
+        projectile_query.iter_mut()
+    {
+        // arrows that used up their hits are already being despawned
+        let spent = hurt_box
+            .map(|hurt_box| hurt_box.max_hits == 0)
+            .unwrap_or(false);
+        if projectile.landed {
+            if spent {
+                continue;
+            }
+            if let Some(mut entity_commands) = commands.get_entity(projectile_entity) {
+                entity_commands
+                    .remove::<(Projectile, HurtBox, HurtBoxDespawner)>()
+                    .insert(FramesToLive::new(PROJECTILE_STUCK_FRAMES));
+            }
+            continue;
+        }
+        projectile.previous_translation = Some(projectile_transform.translation);
         projectile_transform.translation += projectile.velocity * time.period.as_secs_f32();
         projectile_transform.rotation = Vec2::angle_between(Vec2::X, projectile.velocity);
-        projectile.velocity.y -= time.period.as_secs_f32() * 800.;
+        projectile.velocity.y -= time.period.as_secs_f32() * projectile.gravity;
+        if !spent
+            && projectile.velocity.y < 0.
+            && projectile_transform.translation.y <= projectile.ground
+        {
+            projectile_transform.translation.y = projectile.ground;
+            projectile.landed = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // steps a projectile the same way projectile_update does until it falls back to the target height
+    fn landing_x(velocity: Vec2, gravity: f32, target_y: f32) -> f32 {
+        let delta = 1. / 120.;
+        let mut position = Vec2::ZERO;
+        let mut velocity = velocity;
+        while velocity.y >= 0. || position.y > target_y {
+            position += velocity * delta;
+            velocity.y -= delta * gravity;
+        }
+        position.x
+    }
+
+    #[test]
+    fn ballistic_velocity_hits_target_in_range() {
+        for offset in [
+            Vec2::new(600., 0.),
+            Vec2::new(-600., 0.),
+            Vec2::new(400., -50.),
+        ] {
+            let velocity = ballistic_velocity(offset, 1000., 1000.);
+            assert!((velocity.length() - 1000.).abs() < 0.1);
+            assert!((landing_x(velocity, 1000., offset.y) - offset.x).abs() < 20.);
+        }
+    }
+
+    #[test]
+    fn ballistic_velocity_hits_target_from_launch_height() {
+        // archers loose from above their feet at a target standing on the ground
+        let unit_position = Vec2::new(200., -300.);
+        let launch_position = unit_position + Vec2::new(0., 90.);
+        let target = Vec2::new(900., -300.);
+        let offset = target - launch_position;
+        let velocity = ballistic_velocity(offset, 1000., 1000.);
+        assert!((landing_x(velocity, 1000., offset.y) - offset.x).abs() < 20.);
+        // solving from the feet instead overshoots
+        let velocity = ballistic_velocity(target - unit_position, 1000., 1000.);
+        assert!(landing_x(velocity, 1000., offset.y) - offset.x > 20.);
+    }
+
+    #[test]
+    fn ballistic_velocity_falls_back_to_45_degrees_out_of_range() {
+        let velocity = ballistic_velocity(Vec2::new(5000., 0.), 1000., 1000.);
+        assert!(
+            (velocity.angle_between(Vec2::X).abs() - std::f32::consts::FRAC_PI_4).abs() < 0.001
+        );
+        assert!(velocity.x > 0.);
+        let velocity = ballistic_velocity(Vec2::new(-5000., 0.), 1000., 1000.);
+        assert!(velocity.x < 0. && velocity.y > 0.);
+        assert!((velocity.length() - 1000.).abs() < 0.1);
     }
 }
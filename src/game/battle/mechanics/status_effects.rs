@@ -0,0 +1,293 @@
+use bevy::prelude::*;
+use enum_map::Enum;
+use strum_macros::EnumIter;
+
+use crate::{
+    BattlePhase, BattleState, DamageCause, DamageInflictEvent, DamageKind, DamageModifiers,
+    DamageSource, EventSet, UpdateSet,
+};
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum StatusEffectsSystem {
+    Update,
+}
+
+pub struct StatusEffectsPlugin;
+
+impl Plugin for StatusEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            status_effects_update
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(StatusEffectsSystem::Update)
+                .in_set(UpdateSet)
+                .in_set(EventSet::<DamageInflictEvent>::Sender),
+        );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Enum, EnumIter)]
+pub enum StatusEffectKind {
+    Slowed,
+    Frozen,
+    Burning,
+    Soaked,
+    Blinded,
+    Panicked,
+    Poisoned,
+}
+
+impl StatusEffectKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Slowed => "Slowed",
+            Self::Frozen => "Frozen",
+            Self::Burning => "Burning",
+            Self::Soaked => "Soaked",
+            Self::Blinded => "Blinded",
+            Self::Panicked => "Panicked",
+            Self::Poisoned => "Poisoned",
+        }
+    }
+
+    pub fn definition(&self) -> StatusEffectDefinition {
+        let neutral = StatusEffectDefinition::default();
+        match self {
+            Self::Slowed => StatusEffectDefinition {
+                slow_walk: true,
+                ..neutral
+            },
+            Self::Frozen => StatusEffectDefinition {
+                speed_multiplier: 0.3,
+                ..neutral
+            },
+            Self::Burning => StatusEffectDefinition {
+                on_tick: Some(|effect, context| {
+                    context.damage(effect, 5., DamageKind::Magic, DamageCause::Combustion);
+                }),
+                ..neutral
+            },
+            Self::Soaked => neutral,
+            Self::Blinded => StatusEffectDefinition {
+                attacks_blindly: true,
+                ..neutral
+            },
+            Self::Panicked => StatusEffectDefinition {
+                speed_override: Some(300.),
+                retreats: true,
+                ..neutral
+            },
+            Self::Poisoned => StatusEffectDefinition {
+                max_stacks: 5,
+                on_tick: Some(|effect, context| {
+                    context.damage(effect, 2., DamageKind::Flesh, DamageCause::Poison);
+                }),
+                ..neutral
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct StatusEffectDefinition {
+    pub max_stacks: usize,
+    pub speed_multiplier: f32,
+    pub speed_override: Option<f32>,
+    pub slow_walk: bool,
+    pub attacks_blindly: bool,
+    pub retreats: bool,
+    pub on_tick: Option<fn(&StatusEffect, &mut StatusEffectTickContext)>,
+}
+
+impl Default for StatusEffectDefinition {
+    fn default() -> Self {
+        Self {
+            max_stacks: 1,
+            speed_multiplier: 1.,
+            speed_override: None,
+            slow_walk: false,
+            attacks_blindly: false,
+            retreats: false,
+            on_tick: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    // None lasts until the unit dies
+    pub duration: Option<f32>,
+    pub stacks: usize,
+    pub source: Option<Entity>,
+    pub damage_source: DamageSource,
+}
+
+impl StatusEffect {
+    pub fn new(kind: StatusEffectKind, damage_source: DamageSource) -> Self {
+        Self {
+            kind,
+            duration: None,
+            stacks: 1,
+            source: None,
+            damage_source,
+        }
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn with_source(mut self, source: Entity) -> Self {
+        self.source = Some(source);
+        self
+    }
+}
+
+pub struct StatusEffectTickContext {
+    pub entity: Entity,
+    pub delta_seconds: f32,
+    pub damage_inflict_events: Vec<DamageInflictEvent>,
+}
+
+impl StatusEffectTickContext {
+    pub fn damage(
+        &mut self,
+        effect: &StatusEffect,
+        damage_per_second: f32,
+        damage_kind: DamageKind,
+        cause: DamageCause,
+    ) {
+        self.damage_inflict_events.push(DamageInflictEvent {
+            entity: self.entity,
+            damage: self.delta_seconds * damage_per_second * effect.stacks as f32,
+            damage_kind,
+            damage_modifiers: DamageModifiers::default(),
+            slow: false,
+            resisted: false,
+            contact: None,
+            knockback: Vec2::ZERO,
+            source: DamageSource {
+                cause,
+                ..effect.damage_source
+            },
+        });
+    }
+}
+
+#[derive(Default, Clone, Component)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn apply(&mut self, effect: StatusEffect) {
+        let max_stacks = effect.kind.definition().max_stacks;
+        if let Some(existing) = self.effects.iter_mut().find(|e| e.kind == effect.kind) {
+            existing.stacks = (existing.stacks + effect.stacks).min(max_stacks);
+            existing.duration = match (existing.duration, effect.duration) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                _ => None,
+            };
+            existing.source = effect.source.or(existing.source);
+            existing.damage_source = effect.damage_source;
+        } else {
+            self.effects.push(StatusEffect {
+                stacks: effect.stacks.min(max_stacks),
+                ..effect
+            });
+        }
+    }
+
+    pub fn remove(&mut self, kind: StatusEffectKind) {
+        self.effects.retain(|effect| effect.kind != kind);
+    }
+
+    pub fn get(&self, kind: StatusEffectKind) -> Option<&StatusEffect> {
+        self.effects.iter().find(|effect| effect.kind == kind)
+    }
+
+    pub fn has(&self, kind: StatusEffectKind) -> bool {
+        self.get(kind).is_some()
+    }
+
+    pub fn stacks(&self, kind: StatusEffectKind) -> usize {
+        self.get(kind).map(|effect| effect.stacks).unwrap_or(0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StatusEffect> {
+        self.effects.iter()
+    }
+
+    pub fn speed(&self, speed: f32, speed_slow: f32) -> f32 {
+        let mut base_speed = speed;
+        let mut speed_override = None;
+        let mut multiplier = 1.;
+        for effect in self.effects.iter() {
+            let definition = effect.kind.definition();
+            if definition.slow_walk {
+                base_speed = speed_slow;
+            }
+            if definition.speed_override.is_some() {
+                speed_override = definition.speed_override;
+            }
+            multiplier *= definition.speed_multiplier.powi(effect.stacks as i32);
+        }
+        speed_override.unwrap_or(base_speed) * multiplier
+    }
+
+    pub fn retreating(&self) -> bool {
+        self.effects
+            .iter()
+            .any(|effect| effect.kind.definition().retreats)
+    }
+
+    pub fn wants_to_attack(&self, feeling: bool) -> bool {
+        let attacks_blindly = self
+            .effects
+            .iter()
+            .any(|effect| effect.kind.definition().attacks_blindly);
+        feeling && !self.retreating() || attacks_blindly
+    }
+
+    pub fn tick(&mut self, delta_seconds: f32) {
+        for effect in self.effects.iter_mut() {
+            if let Some(duration) = effect.duration.as_mut() {
+                *duration -= delta_seconds;
+            }
+        }
+        self.effects.retain(|effect| {
+            effect
+                .duration
+                .map(|duration| duration > 0.)
+                .unwrap_or(true)
+        });
+    }
+}
+
+pub fn status_effects_update(
+    mut status_effects_query: Query<(Entity, &mut StatusEffects)>,
+    mut damage_inflict_events: EventWriter<DamageInflictEvent>,
+    battle_state: Res<BattleState>,
+    time: Res<FixedTime>,
+) {
+    let delta_seconds = time.period.as_secs_f32();
+    for (entity, mut status_effects) in status_effects_query.iter_mut() {
+        status_effects.tick(delta_seconds);
+        if !(battle_state.battling() && battle_state.phase() == BattlePhase::Battling) {
+            continue;
+        }
+        let mut context = StatusEffectTickContext {
+            entity,
+            delta_seconds,
+            damage_inflict_events: vec![],
+        };
+        for effect in status_effects.iter() {
+            if let Some(on_tick) = effect.kind.definition().on_tick {
+                on_tick(effect, &mut context);
+            }
+        }
+        damage_inflict_events.send_batch(context.damage_inflict_events);
+    }
+}
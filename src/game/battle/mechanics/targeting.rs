@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    BattleRng, Broadphase, DefenseKind, Health, HitBox, StatusEffects, Target, Unit, UpdateSet,
+};
+
+const TARGET_SEARCH_DISTANCE: f32 = 2500.;
+pub const TARGET_RETARGET_INTERVAL: f32 = 0.5;
+const TARGET_CLUSTER_RADIUS: f32 = 200.;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum TargetingSystem {
+    Update,
+}
+
+pub struct TargetingPlugin;
+
+impl Plugin for TargetingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            targeting_update
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(TargetingSystem::Update)
+                .in_set(UpdateSet),
+        );
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum TargetPriority {
+    #[default]
+    Nearest,
+    LowestHealth,
+    Armored,
+    Cluster,
+}
+
+#[derive(Clone, Copy)]
+pub struct UnitTarget {
+    pub entity: Entity,
+    pub offset: Vec2,
+}
+
+struct TargetCandidate {
+    entity: Entity,
+    position: Vec2,
+    distance: f32,
+    health: f32,
+    armored: bool,
+}
+
+impl TargetPriority {
+    // lower is better, distance breaks ties so every profile still prefers close targets
+    fn score(&self, candidate: &TargetCandidate, broadphase: &Broadphase, unit: &Unit) -> f32 {
+        let priority = match self {
+            Self::Nearest => 0.,
+            Self::LowestHealth => candidate.health,
+            Self::Armored => {
+                if candidate.armored {
+                    0.
+                } else {
+                    1.
+                }
+            }
+            Self::Cluster => {
+                -(broadphase
+                    .query_region(
+                        candidate.position - TARGET_CLUSTER_RADIUS,
+                        candidate.position + TARGET_CLUSTER_RADIUS,
+                    )
+                    .filter(|entry| entry.flags.intersects(unit.team.hurt_flags()))
+                    .count() as f32)
+            }
+        };
+        priority * TARGET_SEARCH_DISTANCE * 2. + candidate.distance
+    }
+}
+
+pub fn targeting_update(
+    mut unit_query: Query<(Entity, &mut Unit, &StatusEffects, &GlobalTransform)>,
+    target_query: Query<(&Target, &Health, &HitBox, &GlobalTransform)>,
+    broadphase: Res<Broadphase>,
+    time: Res<FixedTime>,
+    mut battle_rng: ResMut<BattleRng>,
+) {
+    for (unit_entity, mut unit, status_effects, unit_transform) in unit_query.iter_mut() {
+        let position = unit_transform.translation().truncate();
+        let had_target = unit.target.is_some();
+        unit.target = unit.target.and_then(|target| {
+            let (target_team, health, _, target_transform) =
+                target_query.get(target.entity).ok()?;
+            (target_team.team != unit.team && health.current > 0.).then(|| UnitTarget {
+                entity: target.entity,
+                offset: target_transform.translation().truncate() - position,
+            })
+        });
+        if status_effects.retreating() {
+            unit.target = None;
+            continue;
+        }
+        unit.retarget_time -= time.period.as_secs_f32();
+        let lost_target = had_target && unit.target.is_none();
+        if unit.retarget_time > 0. && !lost_target {
+            continue;
+        }
+        // staggered so units spread the cost of searching over several ticks
+        unit.retarget_time = TARGET_RETARGET_INTERVAL * battle_rng.gen_range(0.5..1.5);
+        let priority = unit.stats.target_priority;
+        let mut best = None;
+        for entry in broadphase.query_region(
+            position - TARGET_SEARCH_DISTANCE,
+            position + TARGET_SEARCH_DISTANCE,
+        ) {
+            if entry.entity == unit_entity || !entry.flags.intersects(unit.team.hurt_flags()) {
+                continue;
+            }
+            let Ok((target, health, hit_box, target_transform)) = target_query.get(entry.entity)
+            else {
+                continue;
+            };
+            if target.team == unit.team || health.current <= 0. {
+                continue;
+            }
+            let target_position = target_transform.translation().truncate();
+            let candidate = TargetCandidate {
+                entity: entry.entity,
+                position: target_position,
+                distance: target_position.distance(position),
+                health: health.current,
+                armored: hit_box.defense_kind == DefenseKind::Armor,
+            };
+            let score = priority.score(&candidate, &broadphase, &unit);
+            if best
+                .as_ref()
+                .map(|(best_score, _)| score < *best_score)
+                .unwrap_or(true)
+            {
+                best = Some((score, candidate));
+            }
+        }
+        unit.target = best.map(|(_, candidate)| UnitTarget {
+            entity: candidate.entity,
+            offset: candidate.position - position,
+        });
+    }
+}
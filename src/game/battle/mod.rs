@@ -1,9 +1,29 @@
 mod battle;
+mod battle_rng;
+mod camera;
 mod depths;
 mod entities;
+mod formation;
+mod intervention_controls;
 mod mechanics;
+mod overlay;
+mod replay;
+mod simulation;
+mod time_controls;
+mod unit_definitions;
+mod victory;
 
 pub use battle::*;
+pub use battle_rng::*;
+pub use camera::*;
 pub use depths::*;
 pub use entities::*;
+pub use formation::*;
+pub use intervention_controls::*;
 pub use mechanics::*;
+pub use overlay::*;
+pub use replay::*;
+pub use simulation::*;
+pub use time_controls::*;
+pub use unit_definitions::*;
+pub use victory::*;
@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+
+use bevy::{prelude::*, sprite::Anchor};
+use rand::prelude::*;
+
+use crate::{
+    AssetLibrary, BattleState, CollisionShape, DamageCause, DamageModifier, DamageReceiveEvent,
+    Depth, DepthLayer, EventSet, Health, HealthSystem, HitBox, Transform2, Unit, UnitSystem,
+    UpdateSet,
+};
+
+const HEALTH_BAR_SIZE: Vec2 = Vec2::new(80., 10.);
+const HEALTH_BAR_BORDER: f32 = 2.;
+const HEALTH_BAR_OFFSET: f32 = 30.;
+const DAMAGE_NUMBER_LIFETIME: f32 = 1.;
+const DAMAGE_NUMBER_SPEED: f32 = 120.;
+const DAMAGE_NUMBER_OVER_TIME_THRESHOLD: f32 = 3.;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum BattleOverlaySystem {
+    Input,
+    SpawnHealthBars,
+    UpdateHealthBars,
+    SpawnDamageNumbers,
+    UpdateDamageNumbers,
+}
+
+pub struct BattleOverlayPlugin;
+
+impl Plugin for BattleOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BattleOverlaySettings>()
+            .add_system(battle_overlay_input.in_set(BattleOverlaySystem::Input))
+            .add_system(
+                battle_overlay_spawn_health_bars.in_set(BattleOverlaySystem::SpawnHealthBars),
+            )
+            .add_system(
+                battle_overlay_update_health_bars
+                    .in_set(BattleOverlaySystem::UpdateHealthBars)
+                    .after(BattleOverlaySystem::Input)
+                    .after(BattleOverlaySystem::SpawnHealthBars),
+            )
+            .add_system(
+                battle_overlay_spawn_damage_numbers
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(BattleOverlaySystem::SpawnDamageNumbers)
+                    .in_set(UpdateSet)
+                    .after(EventSet::<DamageReceiveEvent>::Sender)
+                    .after(HealthSystem::ReceiveDamage)
+                    .before(UnitSystem::Die),
+            )
+            .add_system(
+                battle_overlay_update_damage_numbers
+                    .in_set(BattleOverlaySystem::UpdateDamageNumbers),
+            );
+    }
+}
+
+#[derive(Default, Resource)]
+pub struct BattleOverlaySettings {
+    pub health_bars: bool,
+    pub damage_numbers: bool,
+}
+
+#[derive(Component)]
+struct HealthBar {
+    unit_entity: Entity,
+    height: f32,
+}
+
+#[derive(Component)]
+struct HealthBarFill;
+
+#[derive(Component)]
+struct DamageNumber {
+    velocity: Vec2,
+    lifetime: f32,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum DamageNumberKind {
+    #[default]
+    Normal,
+    Fire,
+    Ice,
+    FriendlyFire,
+    Resisted,
+}
+
+impl DamageNumberKind {
+    fn color(&self) -> Color {
+        match self {
+            Self::Normal => Color::WHITE,
+            Self::Fire => Color::rgb(1., 0.5, 0.1),
+            Self::Ice => Color::rgb(0.5, 0.8, 1.),
+            Self::FriendlyFire => Color::rgb(0.9, 0.3, 0.9),
+            Self::Resisted => Color::GRAY,
+        }
+    }
+}
+
+fn battle_overlay_input(
+    mut battle_overlay_settings: ResMut<BattleOverlaySettings>,
+    battle_state: Res<BattleState>,
+    keys: Res<Input<KeyCode>>,
+) {
+    if battle_state.battling() && keys.just_pressed(KeyCode::H) {
+        let enabled =
+            !(battle_overlay_settings.health_bars || battle_overlay_settings.damage_numbers);
+        battle_overlay_settings.health_bars = enabled;
+        battle_overlay_settings.damage_numbers = enabled;
+    }
+}
+
+fn battle_overlay_spawn_health_bars(
+    mut commands: Commands,
+    unit_query: Query<(Entity, &HitBox), Added<Unit>>,
+) {
+    for (unit_entity, hit_box) in unit_query.iter() {
+        let height = match hit_box.shape {
+            CollisionShape::Rect { offset, size } => offset.y + size.y * 0.5,
+            _ => 0.,
+        } + HEALTH_BAR_OFFSET;
+        commands
+            .spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(HEALTH_BAR_SIZE + HEALTH_BAR_BORDER * 2.),
+                        color: Color::rgba(0., 0., 0., 0.7),
+                        ..Default::default()
+                    },
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                },
+                Transform2::default(),
+                Depth::from(DepthLayer::Front(0.1)),
+                HealthBar {
+                    unit_entity,
+                    height,
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(HEALTH_BAR_SIZE),
+                            anchor: Anchor::CenterLeft,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    Transform2::from_xy(HEALTH_BAR_SIZE.x * -0.5, 0.),
+                    Depth::from(DepthLayer::Front(0.11)),
+                    HealthBarFill,
+                ));
+            });
+    }
+}
+
+fn battle_overlay_update_health_bars(
+    mut commands: Commands,
+    mut health_bar_query: Query<(
+        Entity,
+        &mut Transform2,
+        &mut Visibility,
+        &HealthBar,
+        &Children,
+    )>,
+    mut health_bar_fill_query: Query<(&mut Transform2, &mut Sprite), Without<HealthBar>>,
+    unit_query: Query<(&Health, &GlobalTransform), With<Unit>>,
+    battle_overlay_settings: Res<BattleOverlaySettings>,
+) {
+    for (
+        health_bar_entity,
+        mut health_bar_transform,
+        mut health_bar_visibility,
+        health_bar,
+        children,
+    ) in health_bar_query.iter_mut()
+    {
+        let Ok((health, unit_transform)) = unit_query.get(health_bar.unit_entity) else {
+            commands.entity(health_bar_entity).despawn_recursive();
+            continue;
+        };
+        health_bar_transform.translation =
+            unit_transform.translation().truncate() + Vec2::new(0., health_bar.height);
+        *health_bar_visibility = if battle_overlay_settings.health_bars && health.current > 0. {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        let health_ratio = (health.current / health.max).clamp(0., 1.);
+        for child in children.iter() {
+            if let Ok((mut fill_transform, mut fill_sprite)) = health_bar_fill_query.get_mut(*child)
+            {
+                fill_transform.scale.x = health_ratio;
+                fill_sprite.color = Color::rgb(1. - health_ratio, health_ratio, 0.1);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct DamageOverTime {
+    damage: f32,
+    kind: DamageNumberKind,
+    effectiveness: f32,
+}
+
+fn battle_overlay_spawn_damage_numbers(
+    mut commands: Commands,
+    mut damage_receive_events: EventReader<DamageReceiveEvent>,
+    mut damage_over_time: Local<HashMap<Entity, DamageOverTime>>,
+    unit_query: Query<(&Unit, &Health, &HitBox, &GlobalTransform)>,
+    battle_overlay_settings: Res<BattleOverlaySettings>,
+    asset_library: Res<AssetLibrary>,
+) {
+    if !battle_overlay_settings.damage_numbers {
+        damage_receive_events.clear();
+        damage_over_time.clear();
+        return;
+    }
+    let mut rng = thread_rng();
+    let mut spawn_damage_number =
+        |entity: Entity, damage: f32, kind: DamageNumberKind, effectiveness: f32| {
+            let Ok((_, _, hit_box, unit_transform)) = unit_query.get(entity) else {
+                return;
+            };
+            let height = match hit_box.shape {
+                CollisionShape::Rect { offset, size } => offset.y + size.y * 0.5,
+                _ => 0.,
+            };
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        format!("{:.0}", damage),
+                        TextStyle {
+                            font: asset_library.font_bold.clone(),
+                            font_size: 32. * effectiveness.sqrt(),
+                            color: kind.color(),
+                        },
+                    )
+                    .with_alignment(TextAlignment::Center),
+                    ..Default::default()
+                },
+                Transform2::from_translation(
+                    unit_transform.translation().truncate()
+                        + Vec2::new(rng.gen_range(-30.0..30.0), height),
+                ),
+                Depth::from(DepthLayer::Front(0.2)),
+                DamageNumber {
+                    velocity: Vec2::new(rng.gen_range(-20.0..20.0), DAMAGE_NUMBER_SPEED),
+                    lifetime: DAMAGE_NUMBER_LIFETIME,
+                },
+            ));
+        };
+    for damage_receive_event in damage_receive_events.iter() {
+        let Ok((unit, health, hit_box, _)) = unit_query.get(damage_receive_event.entity) else {
+            continue;
+        };
+        let kind = if damage_receive_event.resisted {
+            DamageNumberKind::Resisted
+        } else if damage_receive_event.source.team == unit.team
+            && damage_receive_event.source.cause != DamageCause::Combustion
+        {
+            DamageNumberKind::FriendlyFire
+        } else if damage_receive_event.damage_modifiers[DamageModifier::Fire]
+            || damage_receive_event.source.cause == DamageCause::Combustion
+        {
+            DamageNumberKind::Fire
+        } else if damage_receive_event.slow {
+            DamageNumberKind::Ice
+        } else {
+            DamageNumberKind::Normal
+        };
+        // bigger numbers for attacks that are strong against the unit's defense
+        let effectiveness = damage_receive_event
+            .damage_kind
+            .damage_multiplier(hit_box.defense_kind);
+        let damage = damage_receive_event.damage.min(health.max);
+        // burning and poison tick every frame, so their damage is added up into one number
+        if matches!(
+            damage_receive_event.source.cause,
+            DamageCause::Combustion | DamageCause::Poison
+        ) {
+            let accumulated = damage_over_time
+                .entry(damage_receive_event.entity)
+                .or_default();
+            accumulated.damage += damage;
+            accumulated.kind = kind;
+            accumulated.effectiveness = effectiveness;
+            continue;
+        }
+        if damage < 0.5 {
+            continue;
+        }
+        spawn_damage_number(damage_receive_event.entity, damage, kind, effectiveness);
+    }
+    damage_over_time.retain(|entity, accumulated| {
+        let Ok((_, health, _, _)) = unit_query.get(*entity) else {
+            return false;
+        };
+        let dying = health.current <= 0.;
+        if accumulated.damage >= DAMAGE_NUMBER_OVER_TIME_THRESHOLD
+            || dying && accumulated.damage >= 0.5
+        {
+            spawn_damage_number(
+                *entity,
+                accumulated.damage,
+                accumulated.kind,
+                accumulated.effectiveness,
+            );
+            accumulated.damage = 0.;
+        }
+        !dying
+    });
+}
+
+fn battle_overlay_update_damage_numbers(
+    mut commands: Commands,
+    mut damage_number_query: Query<(Entity, &mut Transform2, &mut Text, &mut DamageNumber)>,
+    time: Res<Time>,
+) {
+    for (
+        damage_number_entity,
+        mut damage_number_transform,
+        mut damage_number_text,
+        mut damage_number,
+    ) in damage_number_query.iter_mut()
+    {
+        damage_number.lifetime -= time.delta_seconds();
+        if damage_number.lifetime <= 0. {
+            commands.entity(damage_number_entity).despawn_recursive();
+            continue;
+        }
+        damage_number_transform.translation += damage_number.velocity * time.delta_seconds();
+        if let Some(section) = damage_number_text.sections.get_mut(0) {
+            section
+                .style
+                .color
+                .set_a((damage_number.lifetime / DAMAGE_NUMBER_LIFETIME * 2.).min(1.));
+        }
+    }
+}
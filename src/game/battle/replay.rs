@@ -0,0 +1,372 @@
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BattleConfig, BattleEndedEvent, BattlePhase, BattleStartEvent, BattleState, BattleSystem,
+    EventSet, FixedSet, Health, Intervention, InterventionEvent, InterventionSystem, Interventions,
+    SpawnSet, Transform2, Unit,
+};
+
+// bump whenever the file format or anything that changes how a seed plays out changes,
+// older replays can't be played back faithfully after that
+pub const REPLAY_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum ReplaySystem {
+    Play,
+    Start,
+    Interventions,
+    RecordInterventions,
+    End,
+    Checksum,
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayState>()
+            .add_system(
+                replay_play
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(ReplaySystem::Play)
+                    .in_set(EventSet::<BattleStartEvent>::Sender),
+            )
+            .add_system(
+                replay_start
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(ReplaySystem::Start)
+                    .in_set(SpawnSet)
+                    .after(EventSet::<BattleStartEvent>::Sender)
+                    .after(BattleSystem::Start),
+            )
+            .add_system(
+                replay_interventions
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(ReplaySystem::Interventions)
+                    .before(InterventionSystem::Update),
+            )
+            .add_system(
+                replay_record_interventions
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(ReplaySystem::RecordInterventions)
+                    .after(EventSet::<InterventionEvent>::Sender),
+            )
+            .add_system(
+                replay_end
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(ReplaySystem::End)
+                    .in_base_set(FixedSet::PostUpdate),
+            )
+            .add_system(
+                replay_checksum
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(ReplaySystem::Checksum)
+                    .in_base_set(FixedSet::PostUpdate)
+                    .after(ReplaySystem::End),
+            );
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub config: BattleConfig,
+    // sandbox battles skip the opening splash, which shifts every tick after it
+    pub sandbox: bool,
+    #[serde(default)]
+    pub interventions: Vec<ReplayIntervention>,
+    pub checksums: Vec<u64>,
+}
+
+// the player's mid-battle actions and the tick they landed on
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReplayIntervention {
+    pub tick: usize,
+    pub intervention: Intervention,
+}
+
+impl Replay {
+    pub fn new(config: BattleConfig, sandbox: bool) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed: config.seed,
+            config,
+            sandbox,
+            interventions: vec![],
+            checksums: vec![],
+        }
+    }
+
+    pub fn from_ron(contents: &str) -> Result<Self, ReplayError> {
+        let replay = ron::from_str::<Replay>(contents).map_err(ReplayError::Parse)?;
+        if replay.version != REPLAY_VERSION {
+            return Err(ReplayError::Version(replay.version));
+        }
+        Ok(replay)
+    }
+
+    pub fn battle_config(&self) -> BattleConfig {
+        BattleConfig {
+            seed: self.seed,
+            ..self.config.clone()
+        }
+    }
+}
+
+pub enum ReplayError {
+    Parse(ron::error::SpannedError),
+    Version(u32),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Parse(error) => write!(f, "Replay could not be read: {}", error),
+            ReplayError::Version(version) => write!(
+                f,
+                "Replay is version {}, this build plays version {}",
+                version, REPLAY_VERSION
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct ReplayDivergence {
+    pub tick: usize,
+    pub expected: Option<u64>,
+    pub actual: Option<u64>,
+}
+
+struct ReplayPlayback {
+    replay: Replay,
+    started: bool,
+    tick: usize,
+}
+
+#[derive(Resource, Default)]
+pub struct ReplayState {
+    queued: Option<Replay>,
+    recording: Option<Replay>,
+    playback: Option<ReplayPlayback>,
+    last_replay: Option<Replay>,
+    divergence: Option<ReplayDivergence>,
+    finished: bool,
+}
+
+impl ReplayState {
+    pub fn play(&mut self, replay: Replay) {
+        self.queued = Some(replay);
+    }
+
+    pub fn playing(&self) -> bool {
+        self.queued.is_some() || self.playback.is_some()
+    }
+
+    pub fn last_replay(&self) -> Option<&Replay> {
+        self.last_replay.as_ref()
+    }
+
+    pub fn divergence(&self) -> Option<ReplayDivergence> {
+        self.divergence
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    fn diverge(&mut self, expected: Option<u64>, actual: Option<u64>) {
+        if let Some(playback) = self.playback.take() {
+            warn!(
+                "replay diverged at tick {} (expected {:?}, got {:?})",
+                playback.tick, expected, actual
+            );
+            self.divergence = Some(ReplayDivergence {
+                tick: playback.tick,
+                expected,
+                actual,
+            });
+        }
+    }
+}
+
+fn replay_play(
+    mut replay_state: ResMut<ReplayState>,
+    mut battle_start_events: EventWriter<BattleStartEvent>,
+) {
+    if let Some(replay) = replay_state.queued.take() {
+        battle_start_events.send(BattleStartEvent {
+            config: replay.battle_config(),
+            sandbox: replay.sandbox,
+        });
+        replay_state.playback = Some(ReplayPlayback {
+            replay,
+            started: false,
+            tick: 0,
+        });
+        replay_state.divergence = None;
+        replay_state.finished = false;
+    }
+}
+
+fn replay_start(
+    mut replay_state: ResMut<ReplayState>,
+    mut battle_start_events: EventReader<BattleStartEvent>,
+) {
+    for battle_start_event in battle_start_events.iter() {
+        replay_state.recording = Some(Replay::new(
+            battle_start_event.config.clone(),
+            battle_start_event.sandbox,
+        ));
+        match replay_state.playback.as_mut() {
+            Some(playback) if !playback.started => {
+                playback.started = true;
+            }
+            _ => {
+                replay_state.playback = None;
+            }
+        }
+    }
+}
+
+fn replay_interventions(mut interventions: ResMut<Interventions>, replay_state: Res<ReplayState>) {
+    let Some(playback) = replay_state.playback.as_ref() else {
+        return;
+    };
+    if !playback.started {
+        return;
+    }
+    interventions.clear_requests();
+    for replay_intervention in playback.replay.interventions.iter() {
+        if replay_intervention.tick == playback.tick {
+            interventions.request(replay_intervention.intervention.clone());
+        }
+    }
+}
+
+fn replay_record_interventions(
+    mut replay_state: ResMut<ReplayState>,
+    mut intervention_events: EventReader<InterventionEvent>,
+) {
+    for intervention_event in intervention_events.iter() {
+        if let Some(recording) = replay_state.recording.as_mut() {
+            recording.interventions.push(ReplayIntervention {
+                tick: recording.checksums.len(),
+                intervention: intervention_event.intervention.clone(),
+            });
+        }
+    }
+}
+
+fn replay_checksum(
+    mut replay_state: ResMut<ReplayState>,
+    mut battle_state: ResMut<BattleState>,
+    unit_query: Query<(&Unit, &Transform2, &Health)>,
+) {
+    if !battle_state.battling() {
+        replay_state.recording = None;
+        replay_state.playback = None;
+        return;
+    }
+    // only the fight itself is checked, splash screens take a varying number of ticks
+    if battle_state.phase() != BattlePhase::Battling {
+        return;
+    }
+    let mut checksum = 0u64;
+    for (unit, unit_transform, unit_health) in unit_query.iter() {
+        // summed so the result doesn't depend on query order
+        checksum = checksum.wrapping_add(unit_checksum(&[
+            unit.team as u32,
+            unit.kind as u32,
+            unit_transform.translation.x.to_bits(),
+            unit_transform.translation.y.to_bits(),
+            unit_health.current.to_bits(),
+        ]));
+    }
+    if let Some(recording) = replay_state.recording.as_mut() {
+        recording.checksums.push(checksum);
+    }
+    if let Some(playback) = replay_state.playback.as_mut() {
+        let expected = playback.replay.checksums.get(playback.tick).copied();
+        if expected == Some(checksum) {
+            playback.tick += 1;
+        } else {
+            replay_state.diverge(expected, Some(checksum));
+            battle_state.stop();
+        }
+    }
+}
+
+fn replay_end(
+    mut replay_state: ResMut<ReplayState>,
+    mut battle_ended_events: EventReader<BattleEndedEvent>,
+) {
+    for _ in battle_ended_events.iter() {
+        if let Some(playback) = replay_state.playback.as_ref() {
+            if playback.tick < playback.replay.checksums.len() {
+                let expected = playback.replay.checksums.get(playback.tick).copied();
+                replay_state.diverge(expected, None);
+            } else {
+                replay_state.playback = None;
+                replay_state.finished = true;
+            }
+        }
+        if let Some(recording) = replay_state.recording.take() {
+            replay_state.last_replay = Some(recording);
+        }
+    }
+}
+
+fn unit_checksum(values: &[u32]) -> u64 {
+    // fnv-1a, stable across builds unlike the std hasher
+    let mut hash = 0xcbf29ce484222325u64;
+    for value in values {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BattleModifier, BattleSimulation, UnitComposition};
+
+    #[test]
+    fn replay_plays_back_with_matching_checksums() {
+        let mut config = BattleConfig {
+            friendly_units: UnitComposition {
+                peasants: 20,
+                archers: 5,
+                ..Default::default()
+            },
+            enemy_units: UnitComposition {
+                warriors: 10,
+                mages: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        config.enemy_modifiers.add_stack(BattleModifier::Fire);
+        let mut recording = BattleSimulation::new(config, 42);
+        while recording.tick().is_none() {}
+        let replay = recording.world().resource::<ReplayState>().last_replay();
+        let replay = replay.cloned().unwrap();
+        assert!(!replay.checksums.is_empty());
+
+        let contents = ron::to_string(&replay).unwrap();
+        let replay = Replay::from_ron(&contents).ok().unwrap();
+        let mut playback = BattleSimulation::from_replay(replay.clone());
+        while playback.tick().is_none() {}
+        let played = playback.world().resource::<ReplayState>();
+        assert!(played.finished());
+        assert!(played.divergence().is_none());
+        assert_eq!(played.last_replay().unwrap().checksums, replay.checksums);
+    }
+}
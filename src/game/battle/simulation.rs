@@ -0,0 +1,250 @@
+use std::time::{Duration, Instant};
+
+use bevy::{input::InputPlugin, prelude::*, time::TimeUpdateStrategy};
+
+use crate::{
+    AddFixedEvent, AreaOfEffectTargetingPlugin, AttackTimelinePlugin, AttackTimelineSystem,
+    BannerSpawnEvent, BattleConfig, BattleEndedEvent, BattleOutcome, BattlePlugin, BattleReport,
+    BattleRngPlugin, BattleSplashEndedEvent, BattleSplashPlayEvent, BattleSplashSpawnEvent,
+    BattleStartEvent, BattleState, BattleSystem, BattlefieldSpawnEvent, BroadphasePlugin,
+    BroadphaseSystem, CrowdPlugin, CrowdSystem, DamagePlugin, DamageSystem, EventSet, FeelerPlugin,
+    FeelerSystem, FixedTimestepPlugin, FixedTimestepTransformPlugin, FramesToLivePlugin,
+    FramesToLiveSystem, Health, HealthPlugin, HealthSystem, InterventionSystem,
+    InterventionsPlugin, MoralePlugin, MoraleSystem, ProjectilePlugin, ProjectileSystem, Replay,
+    ReplayPlugin, ReplayState, SetsPlugin, Sfx, StatusEffects, StatusEffectsPlugin,
+    StatusEffectsSystem, TargetingPlugin, TargetingSystem, Unit, UnitDefinitionsPlugin, UnitPlugin,
+    UnitSystem, UpdateSet,
+};
+
+const MAX_TICKS: usize = 120 * 60 * 10;
+
+pub struct BattleSimulationPlugin;
+
+impl Plugin for BattleSimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(BattlePlugin)
+            .add_plugin(BattleRngPlugin)
+            .add_plugin(UnitDefinitionsPlugin)
+            .add_plugin(UnitPlugin)
+            .add_plugin(DamagePlugin)
+            .add_plugin(HealthPlugin)
+            .add_plugin(StatusEffectsPlugin)
+            .add_plugin(MoralePlugin)
+            .add_plugin(InterventionsPlugin)
+            .add_plugin(AttackTimelinePlugin)
+            .add_plugin(BroadphasePlugin)
+            .add_plugin(CrowdPlugin)
+            .add_plugin(ProjectilePlugin)
+            .add_plugin(AreaOfEffectTargetingPlugin)
+            .add_plugin(TargetingPlugin)
+            .add_plugin(FeelerPlugin)
+            .add_plugin(ReplayPlugin)
+            .add_plugin(FixedTimestepTransformPlugin);
+
+        // every battle system runs in a fixed order so a seed always plays out the same battle
+        app.edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+            schedule
+                // despawns reorder the hurt boxes that draw from the rng, so they land at the same point every tick
+                .configure_set(FramesToLiveSystem::Update.before(InterventionSystem::Update))
+                .configure_sets(
+                    (
+                        InterventionSystem::Update,
+                        BroadphaseSystem::Update,
+                        TargetingSystem::Update,
+                        UnitSystem::UpdateFeeler,
+                        UnitSystem::UpdateSpriteDirection,
+                        StatusEffectsSystem::Update,
+                        UnitSystem::Slow,
+                        UnitSystem::Attack,
+                        AttackTimelineSystem::Update,
+                        UnitSystem::Combust,
+                        UnitSystem::Explode,
+                        DamageSystem::Update,
+                        DamageSystem::Events,
+                        BattleSystem::Report,
+                        HealthSystem::ReceiveDamage,
+                    )
+                        .chain(),
+                )
+                .configure_sets(
+                    (
+                        HealthSystem::ReceiveDamage,
+                        FeelerSystem::Update,
+                        ProjectileSystem::Update,
+                        UnitSystem::Die,
+                        BattleSystem::UnitDie,
+                        MoraleSystem::Update,
+                        BattleSystem::Morale,
+                        BattleSystem::EndDetection,
+                        UnitSystem::Update,
+                        CrowdSystem::Update,
+                        BattleSystem::SplashEnded,
+                    )
+                        .chain(),
+                );
+        });
+    }
+}
+
+// stands in for the battlefield, banners and splash screens when nothing is rendered
+struct HeadlessBattlePlugin;
+
+impl Plugin for HeadlessBattlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Sfx>()
+            .add_fixed_event::<BattlefieldSpawnEvent>()
+            .add_fixed_event::<BannerSpawnEvent>()
+            .add_fixed_event::<BattleSplashSpawnEvent>()
+            .add_fixed_event::<BattleSplashPlayEvent>()
+            .add_fixed_event::<BattleSplashEndedEvent>()
+            .add_system(
+                headless_battle_splash
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(UpdateSet)
+                    .in_set(EventSet::<BattleSplashEndedEvent>::Sender)
+                    .after(EventSet::<BattleSplashSpawnEvent>::Sender)
+                    .after(EventSet::<BattleSplashPlayEvent>::Sender),
+            );
+    }
+}
+
+fn headless_battle_splash(
+    mut battle_splash_spawn_events: EventReader<BattleSplashSpawnEvent>,
+    mut battle_splash_play_events: EventReader<BattleSplashPlayEvent>,
+    mut battle_splash_ended_events: EventWriter<BattleSplashEndedEvent>,
+) {
+    for battle_splash_spawn_event in battle_splash_spawn_events.iter() {
+        if battle_splash_spawn_event.play_battle_start {
+            battle_splash_ended_events.send_default();
+        }
+    }
+    for _ in battle_splash_play_events.iter() {
+        battle_splash_ended_events.send_default();
+    }
+}
+
+pub struct BattleSimulation {
+    app: App,
+    instant: Instant,
+    period: Duration,
+}
+
+impl BattleSimulation {
+    pub fn new(config: BattleConfig, seed: u64) -> Self {
+        let mut app = Self::app();
+        app.world.send_event(BattleStartEvent {
+            config: BattleConfig { seed, ..config },
+            sandbox: true,
+        });
+        Self::start(app)
+    }
+
+    // plays the replay back, checking every tick against the recorded checksums
+    pub fn from_replay(replay: Replay) -> Self {
+        let mut app = Self::app();
+        app.world.resource_mut::<ReplayState>().play(replay);
+        Self::start(app)
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(InputPlugin)
+            .add_plugin(SetsPlugin)
+            .add_plugin(FixedTimestepPlugin)
+            .add_plugin(FramesToLivePlugin)
+            .add_plugin(BattleSimulationPlugin)
+            .add_plugin(HeadlessBattlePlugin);
+        app.setup();
+        app
+    }
+
+    fn start(app: App) -> Self {
+        let period = app.world.resource::<FixedTime>().period;
+        let mut simulation = Self {
+            app,
+            instant: Instant::now(),
+            period,
+        };
+        // the first update only starts the clock
+        simulation.update();
+        simulation
+    }
+
+    pub fn world(&self) -> &World {
+        &self.app.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    // advances time by exactly one fixed tick
+    pub fn tick(&mut self) -> Option<BattleReport> {
+        self.update();
+        self.app
+            .world
+            .resource_mut::<Events<BattleEndedEvent>>()
+            .drain()
+            .next()
+            .map(|battle_ended_event| battle_ended_event.report)
+    }
+
+    // what the battle looks like so far, for when it has to be cut short
+    pub fn timeout_report(&mut self) -> BattleReport {
+        let world = &mut self.app.world;
+        let mut report = world.resource::<BattleState>().report().clone();
+        report.outcome = BattleOutcome::Timeout;
+        let mut unit_query = world.query::<(&Unit, &Health, &StatusEffects)>();
+        report.count_survivors(unit_query.iter(world));
+        report
+    }
+
+    fn update(&mut self) {
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualInstant(self.instant));
+        self.app.update();
+        self.instant += self.period;
+    }
+}
+
+pub fn simulate_battle(config: BattleConfig, seed: u64) -> BattleReport {
+    let mut simulation = BattleSimulation::new(config, seed);
+    for _ in 0..MAX_TICKS {
+        if let Some(report) = simulation.tick() {
+            return report;
+        }
+    }
+    warn!("battle simulation did not finish after {} ticks", MAX_TICKS);
+    simulation.timeout_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UnitComposition;
+
+    #[test]
+    fn same_seed_plays_out_the_same_battle() {
+        let config = BattleConfig {
+            friendly_units: UnitComposition {
+                peasants: 20,
+                warriors: 10,
+                archers: 10,
+                mages: 4,
+                brutes: 2,
+            },
+            enemy_units: UnitComposition {
+                peasants: 25,
+                warriors: 8,
+                archers: 12,
+                mages: 3,
+                brutes: 2,
+            },
+            ..Default::default()
+        };
+        let report = simulate_battle(config.clone(), 7);
+        assert!(report.ticks > 0);
+        assert!(report == simulate_battle(config, 7));
+    }
+}
@@ -0,0 +1,275 @@
+use bevy::prelude::*;
+
+use crate::{
+    AppState, AssetLibrary, BattleState, BattlefieldSpawnEvent, CameraAnchor, Clickable,
+    ClickableSystem, CollisionShape, Depth, EventSet, Sfx, SfxKind, SpawnSet, Transform2,
+};
+
+pub const BATTLE_SPEEDS: [f32; 5] = [0.25, 0.5, 1., 2., 4.];
+const BATTLE_SPEED_NORMAL: usize = 2;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub enum BattleTimeSystem {
+    Input,
+    Update,
+    Spawn,
+    UpdateButtons,
+    FixedUpdate,
+}
+
+pub struct BattleTimePlugin;
+
+impl Plugin for BattleTimePlugin {
+    fn build(&self, app: &mut App) {
+        // bevy's own fixed update runner only knows the unscaled frame time, so it is switched
+        // off and the fixed update schedule runs from here, right where it would have run
+        app.init_resource::<BattleTime>()
+            .configure_set(CoreSet::FixedUpdate.run_if(|| false))
+            .add_system(
+                battle_time_fixed_update
+                    .in_base_set(CoreSet::StateTransitions)
+                    .in_set(BattleTimeSystem::FixedUpdate)
+                    .after(apply_state_transition::<AppState>),
+            )
+            .add_system(battle_time_input.in_set(BattleTimeSystem::Input))
+            .add_system(
+                battle_time_update_buttons
+                    .in_set(BattleTimeSystem::UpdateButtons)
+                    .after(ClickableSystem),
+            )
+            .add_system(
+                battle_time_update
+                    .in_set(BattleTimeSystem::Update)
+                    .after(BattleTimeSystem::Input)
+                    .after(BattleTimeSystem::UpdateButtons),
+            )
+            .add_system(
+                battle_time_spawn_buttons
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(BattleTimeSystem::Spawn)
+                    .in_set(SpawnSet)
+                    .after(EventSet::<BattlefieldSpawnEvent>::Sender),
+            );
+    }
+}
+
+// scales how much time FixedTime accumulates, so the simulation ticks are identical at any speed
+// while Time, and everything animated from it, keeps running at its own pace
+#[derive(Resource)]
+pub struct BattleTime {
+    speed: usize,
+    paused: bool,
+    step: bool,
+}
+
+impl Default for BattleTime {
+    fn default() -> Self {
+        Self {
+            speed: BATTLE_SPEED_NORMAL,
+            paused: false,
+            step: false,
+        }
+    }
+}
+
+impl BattleTime {
+    pub fn speed(&self) -> f32 {
+        BATTLE_SPEEDS[self.speed]
+    }
+
+    pub fn speed_index(&self) -> usize {
+        self.speed
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        if self.paused {
+            0.
+        } else {
+            self.speed()
+        }
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn set_speed(&mut self, speed: usize) {
+        self.speed = speed.min(BATTLE_SPEEDS.len() - 1);
+    }
+
+    pub fn faster(&mut self) {
+        self.set_speed(self.speed + 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    pub fn step(&mut self) {
+        if self.paused {
+            self.step = true;
+        }
+    }
+}
+
+#[derive(Component)]
+struct BattleTimeButton {
+    kind: BattleTimeButtonKind,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BattleTimeButtonKind {
+    Pause,
+    Speed(usize),
+    Step,
+}
+
+impl BattleTimeButtonKind {
+    fn label(&self) -> String {
+        match self {
+            Self::Pause => "Pause".to_owned(),
+            Self::Speed(speed) => format!("{}x", BATTLE_SPEEDS[*speed]),
+            Self::Step => "Step".to_owned(),
+        }
+    }
+
+    fn active(&self, battle_time: &BattleTime) -> bool {
+        match self {
+            Self::Pause => battle_time.paused,
+            Self::Speed(speed) => battle_time.speed == *speed,
+            Self::Step => false,
+        }
+    }
+}
+
+fn battle_time_input(
+    mut battle_time: ResMut<BattleTime>,
+    battle_state: Res<BattleState>,
+    keys: Res<Input<KeyCode>>,
+) {
+    if !battle_state.battling() {
+        return;
+    }
+    if keys.just_pressed(KeyCode::P) {
+        let paused = battle_time.paused();
+        battle_time.set_paused(!paused);
+    }
+    if keys.just_pressed(KeyCode::Minus) || keys.just_pressed(KeyCode::NumpadSubtract) {
+        battle_time.slower();
+    }
+    if keys.just_pressed(KeyCode::Equals) || keys.just_pressed(KeyCode::NumpadAdd) {
+        battle_time.faster();
+    }
+    if keys.just_pressed(KeyCode::Period) {
+        battle_time.step();
+    }
+}
+
+fn battle_time_fixed_update(world: &mut World) {
+    let time_scale = world.resource::<BattleTime>().time_scale();
+    if time_scale > 0. {
+        let delta = world.resource::<Time>().delta();
+        world
+            .resource_mut::<FixedTime>()
+            .tick(delta.mul_f32(time_scale));
+    }
+    while world.resource_mut::<FixedTime>().expend().is_ok() {
+        world.run_schedule(CoreSchedule::FixedUpdate);
+    }
+}
+
+fn battle_time_update(
+    mut battle_time: ResMut<BattleTime>,
+    mut fixed_time: ResMut<FixedTime>,
+    battle_state: Res<BattleState>,
+) {
+    if !battle_state.battling() {
+        *battle_time = BattleTime::default();
+    }
+    if battle_time.step {
+        battle_time.step = false;
+        let period = fixed_time.period;
+        fixed_time.tick(period);
+    }
+}
+
+fn battle_time_spawn_buttons(
+    mut commands: Commands,
+    mut spawn_events: EventReader<BattlefieldSpawnEvent>,
+    asset_library: Res<AssetLibrary>,
+) {
+    for _ in spawn_events.iter() {
+        let mut kinds = vec![BattleTimeButtonKind::Pause];
+        kinds.extend((0..BATTLE_SPEEDS.len()).map(BattleTimeButtonKind::Speed));
+        kinds.push(BattleTimeButtonKind::Step);
+        for (index, kind) in kinds.into_iter().enumerate() {
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        kind.label(),
+                        TextStyle {
+                            font: asset_library.font_normal.clone(),
+                            font_size: 32.,
+                            color: Color::GRAY,
+                        },
+                    )
+                    .with_alignment(TextAlignment::Center),
+                    ..Default::default()
+                },
+                Clickable {
+                    shape: CollisionShape::Rect {
+                        offset: Vec2::ZERO,
+                        size: Vec2::new(90., 40.),
+                    },
+                    ..Default::default()
+                },
+                Transform2::from_xy(300. + index as f32 * 90., 510.),
+                CameraAnchor {
+                    offset: Vec2::new(300. + index as f32 * 90., 510.),
+                },
+                Depth::Exact(0.9),
+                BattleTimeButton { kind },
+            ));
+        }
+    }
+}
+
+fn battle_time_update_buttons(
+    mut button_query: Query<(&mut Text, &Clickable, &BattleTimeButton)>,
+    mut battle_time: ResMut<BattleTime>,
+    mut sfx: ResMut<Sfx>,
+) {
+    for (mut button_text, button_clickable, button) in button_query.iter_mut() {
+        if button_clickable.just_hovered() {
+            sfx.play(SfxKind::UiButtonHover);
+        }
+        if button_clickable.confirmed {
+            sfx.play(SfxKind::UiButtonClick);
+            match button.kind {
+                BattleTimeButtonKind::Pause => {
+                    let paused = battle_time.paused();
+                    battle_time.set_paused(!paused);
+                }
+                BattleTimeButtonKind::Speed(speed) => {
+                    battle_time.set_speed(speed);
+                    battle_time.set_paused(false);
+                }
+                BattleTimeButtonKind::Step => {
+                    battle_time.set_paused(true);
+                    battle_time.step();
+                }
+            }
+        }
+        if let Some(section) = button_text.sections.get_mut(0) {
+            section.style.color = if button_clickable.hovered || button.kind.active(&battle_time) {
+                Color::WHITE
+            } else {
+                Color::GRAY
+            };
+        }
+    }
+}
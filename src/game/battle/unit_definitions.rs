@@ -0,0 +1,90 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use enum_map::EnumMap;
+use serde::Deserialize;
+
+use crate::{AssetLibrary, Attack, AttackStats, UnitKind, UnitStats};
+
+pub struct UnitDefinitionsPlugin;
+
+impl Plugin for UnitDefinitionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UnitDefinitions>();
+        if app.world.contains_resource::<AssetLibrary>() {
+            app.add_asset::<UnitDefinitions>()
+                .init_asset_loader::<UnitDefinitionsLoader>()
+                .add_system(unit_definitions_reload);
+        }
+    }
+}
+
+#[derive(Resource, Clone, Deserialize, TypeUuid)]
+#[uuid = "5b1f4a0e-8c2d-4e7a-9b61-3f0c2d8e7a14"]
+pub struct UnitDefinitions {
+    units: EnumMap<UnitKind, UnitStats>,
+    attacks: EnumMap<Attack, AttackStats>,
+}
+
+impl Default for UnitDefinitions {
+    fn default() -> Self {
+        // headless simulations have no asset server, so they use the definitions from build time
+        ron::from_str(include_str!("../../../assets/rattoria.units.ron")).unwrap()
+    }
+}
+
+impl UnitDefinitions {
+    pub fn unit_stats(&self, unit_kind: UnitKind) -> &UnitStats {
+        &self.units[unit_kind]
+    }
+
+    pub fn attack_stats(&self, attack: Attack) -> &AttackStats {
+        &self.attacks[attack]
+    }
+}
+
+#[derive(Default)]
+struct UnitDefinitionsLoader;
+
+impl AssetLoader for UnitDefinitionsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let unit_definitions: UnitDefinitions = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(unit_definitions));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["units.ron"]
+    }
+}
+
+// units already on the battlefield keep the stats they spawned with
+fn unit_definitions_reload(
+    mut unit_definitions: ResMut<UnitDefinitions>,
+    mut asset_events: EventReader<AssetEvent<UnitDefinitions>>,
+    unit_definitions_assets: Res<Assets<UnitDefinitions>>,
+    asset_library: Res<AssetLibrary>,
+) {
+    for asset_event in asset_events.iter() {
+        match asset_event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                if *handle == asset_library.units {
+                    if let Some(asset) = unit_definitions_assets.get(handle) {
+                        *unit_definitions = asset.clone();
+                        info!("reloaded unit definitions");
+                    }
+                }
+            }
+            AssetEvent::Removed { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use enum_map::EnumMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{Team, UnitKind};
+
+pub const BANNER_CAPTURE_DISTANCE: f32 = 200.;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VictoryCondition {
+    Annihilate,
+    Survive { seconds: f32 },
+    KillTarget { unit_kind: UnitKind },
+    ProtectBanner,
+    CaptureBanner,
+    LimitLosses { max_losses: usize },
+}
+
+impl VictoryCondition {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Annihilate => "Annihilate",
+            Self::Survive { .. } => "Survive",
+            Self::KillTarget { .. } => "Kill Target",
+            Self::ProtectBanner => "Protect Banner",
+            Self::CaptureBanner => "Capture Banner",
+            Self::LimitLosses { .. } => "Limit Losses",
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Annihilate => "Defeat every enemy".to_owned(),
+            Self::Survive { seconds } => format!("Survive for {:.0} seconds", seconds),
+            Self::KillTarget { unit_kind } => {
+                format!("Defeat the enemy {}", unit_kind.name_plural())
+            }
+            Self::ProtectBanner => "Keep the enemy away from your banner".to_owned(),
+            Self::CaptureBanner => "Reach the enemy banner".to_owned(),
+            Self::LimitLosses { max_losses } => format!("Lose at most {} units", max_losses),
+        }
+    }
+
+    pub fn status(&self, context: &VictoryContext) -> VictoryConditionStatus {
+        let friendly_standing = context.standing[Team::Friendly];
+        let enemy_standing = context.standing[Team::Enemy];
+        if friendly_standing == 0 {
+            return VictoryConditionStatus::Failed;
+        }
+        let met = match self {
+            Self::Annihilate => enemy_standing == 0,
+            Self::Survive { seconds } => context.duration >= *seconds || enemy_standing == 0,
+            Self::KillTarget { unit_kind } => context.enemies_alive[*unit_kind] == 0,
+            Self::ProtectBanner => {
+                if context.banner_reached[Team::Friendly] {
+                    return VictoryConditionStatus::Failed;
+                }
+                enemy_standing == 0 || context.timed_out
+            }
+            Self::CaptureBanner => context.banner_reached[Team::Enemy],
+            Self::LimitLosses { max_losses } => {
+                if context.friendly_losses > *max_losses {
+                    return VictoryConditionStatus::Failed;
+                }
+                enemy_standing == 0 || context.timed_out
+            }
+        };
+        if met {
+            VictoryConditionStatus::Met
+        } else {
+            VictoryConditionStatus::Pending
+        }
+    }
+
+    // an empty list falls back to the classic fight to the last rat
+    pub fn evaluate(
+        victory_conditions: &[VictoryCondition],
+        context: &VictoryContext,
+    ) -> Option<BattleOutcome> {
+        if context.standing[Team::Friendly] == 0 && context.standing[Team::Enemy] == 0 {
+            return Some(BattleOutcome::Draw);
+        }
+        let statuses = if victory_conditions.is_empty() {
+            vec![VictoryCondition::Annihilate.status(context)]
+        } else {
+            victory_conditions
+                .iter()
+                .map(|victory_condition| victory_condition.status(context))
+                .collect()
+        };
+        if statuses.contains(&VictoryConditionStatus::Failed) {
+            Some(BattleOutcome::Defeat)
+        } else if statuses
+            .iter()
+            .all(|status| *status == VictoryConditionStatus::Met)
+        {
+            Some(BattleOutcome::Victory)
+        } else if context.timed_out {
+            Some(BattleOutcome::Timeout)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum VictoryConditionStatus {
+    Pending,
+    Met,
+    Failed,
+}
+
+#[derive(Default)]
+pub struct VictoryContext {
+    pub duration: f32,
+    pub timed_out: bool,
+    // units still fighting, routed units don't count
+    pub standing: EnumMap<Team, usize>,
+    pub enemies_alive: EnumMap<UnitKind, usize>,
+    pub friendly_losses: usize,
+    // whether the team's banner has been reached by the opposing team
+    pub banner_reached: EnumMap<Team, bool>,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BattleOutcome {
+    Victory,
+    #[default]
+    Defeat,
+    Draw,
+    Timeout,
+}
+
+impl BattleOutcome {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Victory => "Victory",
+            Self::Defeat => "Defeat",
+            Self::Draw => "Draw",
+            Self::Timeout => "Timeout",
+        }
+    }
+
+    pub fn victory(&self) -> bool {
+        *self == Self::Victory
+    }
+}
@@ -0,0 +1,97 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{AssetLibrary, Banner, BattleModifier, Item, UnitComposition, VictoryCondition};
+
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Campaign>();
+        if app.world.contains_resource::<AssetLibrary>() {
+            app.add_asset::<Campaign>()
+                .init_asset_loader::<CampaignLoader>()
+                .add_system(campaign_reload);
+        }
+    }
+}
+
+#[derive(Resource, Clone, Deserialize, TypeUuid)]
+#[uuid = "9d3c6e21-47b8-4f0a-a5d2-6c1e8b0f3a57"]
+pub struct Campaign {
+    pub war_chefs: Vec<CampaignWarChef>,
+}
+
+impl Default for Campaign {
+    fn default() -> Self {
+        ron::from_str(include_str!("../../assets/campaigns/rattoria.campaign.ron")).unwrap()
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct CampaignWarChef {
+    pub banner: Banner,
+    pub battles: Vec<CampaignBattle>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct CampaignBattle {
+    pub enemy_units: UnitComposition,
+    #[serde(default)]
+    pub enemy_modifiers: Vec<BattleModifier>,
+    pub hint: String,
+    #[serde(default)]
+    pub preplanning_script: Option<String>,
+    #[serde(default)]
+    pub victory_conditions: Vec<VictoryCondition>,
+    #[serde(default)]
+    pub skip_item_dialogues: Vec<Item>,
+    pub food_reward: usize,
+}
+
+#[derive(Default)]
+struct CampaignLoader;
+
+impl AssetLoader for CampaignLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let campaign: Campaign = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(campaign));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["campaign.ron"]
+    }
+}
+
+fn campaign_reload(
+    mut campaign: ResMut<Campaign>,
+    mut asset_events: EventReader<AssetEvent<Campaign>>,
+    campaign_assets: Res<Assets<Campaign>>,
+    asset_library: Res<AssetLibrary>,
+) {
+    for asset_event in asset_events.iter() {
+        match asset_event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                if *handle == asset_library.campaign {
+                    if let Some(asset) = campaign_assets.get(handle) {
+                        *campaign = asset.clone();
+                        info!("reloaded campaign");
+                    }
+                }
+            }
+            AssetEvent::Removed { .. } => {}
+        }
+    }
+}
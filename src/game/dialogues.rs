@@ -1,91 +1,64 @@
-use crate::{Articy, Item, Quest, Script};
+use bevy::prelude::*;
+
+use crate::{Articy, Campaign, Item, ItemDefinitions, Quest, Script};
 
 impl Quest {
-    pub fn next(&mut self) -> bool {
+    pub fn next(&mut self, campaign: &Campaign) -> bool {
         self.battle += 1;
-        if self.war_chef == 0 && self.battle == 3 {
-            self.war_chef = 1;
-            self.battle = 0;
-            true
-        } else if self.war_chef == 1 && self.battle == 3 {
-            self.war_chef = 2;
-            self.battle = 0;
+        let battle_count = campaign
+            .war_chefs
+            .get(self.war_chef)
+            .map(|war_chef| war_chef.battles.len())
+            .unwrap_or(0);
+        if self.battle < battle_count {
             true
-        } else if self.war_chef == 2 && self.battle == 4 {
-            self.war_chef = 3;
+        } else if self.war_chef + 1 < campaign.war_chefs.len() {
+            self.war_chef += 1;
             self.battle = 0;
             true
-        } else if self.war_chef == 3 && self.battle == 4 {
-            self.war_chef = 4;
-            self.battle = 0;
-            true
-        } else if self.war_chef == 4 && self.battle == 3 {
-            false
         } else {
-            true
+            false
         }
     }
 
-    pub fn preplanning_script(&self, articy: &Articy) -> Option<Script> {
-        match self.war_chef {
-            0 => match self.battle {
-                0 => Some("WC1B1"),
-                1 => Some("WC1B2"),
-                2 => Some("WC1B3"),
-                _ => None,
-            },
-            1 => match self.battle {
-                0 => Some("WC2B1"),
-                1 => Some("WC2B2"),
-                2 => Some("WC2B3"),
-                _ => None,
-            },
-            2 => match self.battle {
-                0 => Some("WC3B1"),
-                1 => Some("WC3B2"),
-                2 => Some("WC3B3"),
-                3 => Some("WC3B4"),
-                _ => None,
-            },
-            3 => match self.battle {
-                0 => Some("WC4B1"),
-                1 => Some("WC4B2"),
-                2 => Some("WC4B3"),
-                3 => Some("WC4B4"),
-                _ => None,
-            },
-            4 => match self.battle {
-                0 => Some("WC5B1"),
-                1 => Some("WC5B2"),
-                2 => Some("WC5B3"),
-                _ => None,
-            },
-            _ => None,
-        }
-        .map(|str| Script::new(articy.dialogues[str].clone()))
+    pub fn preplanning_script(&self, campaign: &Campaign, articy: &Articy) -> Option<Script> {
+        self.campaign_battle(campaign)
+            .and_then(|battle| battle.preplanning_script.as_ref())
+            .and_then(|name| {
+                let dialogue = articy.dialogues.get(name);
+                if dialogue.is_none() {
+                    warn!("unknown preplanning_script dialogue: {}", name);
+                }
+                dialogue
+            })
+            .map(|dialogue| Script::new(dialogue.clone()))
     }
 
-    pub fn item_script(&mut self, used_item: Item, articy: &Articy) -> Option<Script> {
-        if !self.seen_item_dialogue[used_item] {
-            self.seen_item_dialogue[used_item] = true;
-            match used_item {
-                Item::BogHardWeeds => {
-                    if self.war_chef == 1 && self.battle == 2 {
-                        None
-                    } else {
-                        Some("BogHardWeeds")
-                    }
-                }
-                Item::CeleryQuartz => Some("CeleryQuartz"),
-                Item::CracklingMoss => Some("CracklingMoss"),
-                Item::AxeShrooms => Some("AxeShrooms"),
-                Item::SquirtBlopBerries => Some("SquirtBlopBerries"),
-                Item::FrostyWebStrands => Some("FrostyWebStrands"),
-                _ => None,
-            }
-            .map(|str| Script::new(articy.dialogues[str].clone()))
-        } else {
-            None
+    pub fn item_script(
+        &mut self,
+        used_item: &Item,
+        campaign: &Campaign,
+        item_definitions: &ItemDefinitions,
+        articy: &Articy,
+    ) -> Option<Script> {
+        if !self.seen_item_dialogue.insert(used_item.clone()) {
+            return None;
+        }
+        if self.campaign_battle(campaign).map_or(false, |battle| {
+            battle.skip_item_dialogues.contains(used_item)
+        }) {
+            return None;
         }
+        item_definitions
+            .get(used_item)
+            .and_then(|definition| definition.first_use_dialogue.as_ref())
+            .and_then(|name| {
+                let dialogue = articy.dialogues.get(name);
+                if dialogue.is_none() {
+                    warn!("unknown first_use_dialogue dialogue: {}", name);
+                }
+                dialogue
+            })
+            .map(|dialogue| Script::new(dialogue.clone()))
     }
 }
@@ -3,8 +3,9 @@ use bevy_egui::{egui, EguiContexts};
 use strum::IntoEnumIterator;
 
 use crate::{
-    Articy, ArticyDialogueInstruction, DebugDrawSettings, Dialogue, DialogueEvent, GameState, Item,
-    PersistentGameState, Script, Sfx, SfxKind, UnitComposition, UnitKind,
+    Articy, ArticyDialogueInstruction, BattleOverlaySettings, Campaign, DebugDrawSettings,
+    Dialogue, DialogueEvent, GameState, ItemDefinitions, PersistentGameState, Script, Sfx, SfxKind,
+    UnitComposition, UnitKind,
 };
 
 pub struct GamePlugin;
@@ -21,6 +22,7 @@ impl Plugin for GamePlugin {
 fn game_dialogue_events(
     mut dialogue_events: EventReader<DialogueEvent>,
     mut game_state: ResMut<GameState>,
+    item_definitions: Res<ItemDefinitions>,
 ) {
     for dialogue_event in dialogue_events.iter() {
         match &dialogue_event.instruction {
@@ -48,9 +50,13 @@ fn game_dialogue_events(
                     game_state.food -= count;
                 }
             }
-            ArticyDialogueInstruction::AddItem(item) => {
-                game_state.loot.add_items(*item, 1);
-                game_state.inventory.add(*item);
+            ArticyDialogueInstruction::AddItem(name) => {
+                if let Some(item_definition) = item_definitions.find_by_name(name) {
+                    game_state.loot.add_items(item_definition.id.clone(), 1);
+                    game_state.inventory.add(item_definition.id.clone());
+                } else {
+                    warn!("unknown AddItem() name: {}", name);
+                }
             }
             ArticyDialogueInstruction::SetGlobalVariable(name, value) => {
                 game_state.global_variables.insert(name.clone(), *value);
@@ -65,10 +71,13 @@ fn game_dialogue_events(
 fn game_debug(
     mut contexts: EguiContexts,
     mut debug_draw_settings: ResMut<DebugDrawSettings>,
+    mut battle_overlay_settings: ResMut<BattleOverlaySettings>,
     mut game_state: ResMut<GameState>,
     mut dialogue: ResMut<Dialogue>,
     mut sfx: ResMut<Sfx>,
     articy: Res<Articy>,
+    campaign: Res<Campaign>,
+    item_definitions: Res<ItemDefinitions>,
 ) {
     if false {
         egui::Window::new("Debug")
@@ -77,6 +86,15 @@ fn game_debug(
                 ui.checkbox(&mut debug_draw_settings.draw_hit_boxes, "Draw Hitboxes");
                 ui.checkbox(&mut debug_draw_settings.draw_hurt_boxes, "Draw Hurtboxes");
                 ui.checkbox(&mut debug_draw_settings.draw_feelers, "Draw Feelers");
+                ui.checkbox(
+                    &mut debug_draw_settings.draw_area_of_effect_targets,
+                    "Draw AoE Targets",
+                );
+                ui.checkbox(&mut battle_overlay_settings.health_bars, "Health Bars");
+                ui.checkbox(
+                    &mut battle_overlay_settings.damage_numbers,
+                    "Damage Numbers",
+                );
                 ui.collapsing("Variables", |ui| {
                     for (name, value) in game_state.global_variables.iter_mut() {
                         ui.checkbox(value, name);
@@ -86,7 +104,7 @@ fn game_debug(
                     ui.label(format!("War Chef: {}", game_state.quest.war_chef));
                     ui.label(format!("Battle: {}", game_state.quest.battle));
                     if ui.button("next").clicked() {
-                        game_state.quest.next();
+                        game_state.quest.next(&campaign);
                     }
                     if ui.button("checkpoint").clicked() {
                         game_state.checkpoint();
@@ -200,9 +218,9 @@ fn game_debug(
                     }
                 });
                 ui.collapsing("Items", |ui| {
-                    for item in Item::iter() {
-                        if ui.button(format!("Add {}", item.name())).clicked() {
-                            game_state.inventory.add(item);
+                    for item_definition in item_definitions.iter() {
+                        if ui.button(format!("Add {}", item_definition.name)).clicked() {
+                            game_state.inventory.add(item_definition.id.clone());
                         }
                     }
                 });
@@ -1,13 +1,18 @@
 use std::mem::take;
 
 use bevy::prelude::*;
+use enum_map::EnumMap;
 
 use crate::{
     in_game_state, not_in_game_state_or_sandbox, AppState, Banner, BattleConfig, BattleEndedEvent,
-    BattleModifier, BattleModifiers, BattleStartEvent, BattleState, GameState, Intel, Item,
-    PlanningEndedEvent, PlanningStartEvent, PlanningState,
+    BattleModifier, BattleRng, BattleStartEvent, BattleState, Campaign, GameState, Intel,
+    IntermissionState, ItemDefinitions, PlanningEndedEvent, PlanningStartEvent, PlanningState,
+    Team,
 };
 
+const INTERVENTION_BUDGET: usize = 3;
+const SICKNESS_SEED_SALT: u64 = 0x5c1c_4e55_d15e_a5e5;
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
 pub enum GameDirectorSystem {
     PlanningEnter,
@@ -59,40 +64,53 @@ fn game_director_planning_enter(mut planning_start_events: EventWriter<PlanningS
 fn game_director_battle_enter(
     mut battle_start_events: EventWriter<BattleStartEvent>,
     mut game_state: ResMut<GameState>,
+    campaign: Res<Campaign>,
+    item_definitions: Res<ItemDefinitions>,
 ) {
     let friendly_units = game_state.get_and_reset_fed_army();
-    let mut friendly_modifiers = BattleModifiers::default();
-    let mut sick = false;
+    let friendly_modifiers = item_definitions.battle_modifiers(game_state.consumed_items.iter());
+    let friendly_unit_modifiers = EnumMap::from_fn(|unit_kind| {
+        item_definitions.battle_modifiers(game_state.fed_items[unit_kind].iter())
+    });
+    let sick = EnumMap::from_fn(|unit_kind| {
+        friendly_modifiers.active(BattleModifier::Sickness)
+            || friendly_unit_modifiers[unit_kind].active(BattleModifier::Sickness)
+    });
     game_state.used_items = vec![];
-    for item in take(&mut game_state.consumed_items) {
-        for modifier in item.modifiers() {
-            friendly_modifiers[modifier] = true;
-            if modifier == BattleModifier::Sickness {
-                sick = true;
-            }
-        }
-        if item == Item::BogHardWeeds {
-            game_state
-                .global_variables
-                .insert("UsedBogHardWeed".to_owned(), true);
+    let mut items = take(&mut game_state.consumed_items);
+    for fed_items in game_state.fed_items.values_mut() {
+        items.extend(take(fed_items));
+    }
+    for item in items {
+        if let Some(used_variable) = item_definitions
+            .get(&item)
+            .and_then(|item_definition| item_definition.used_variable.clone())
+        {
+            game_state.global_variables.insert(used_variable, true);
         }
         game_state.used_items.push(item);
     }
-    game_state.apply_sickness(sick);
+    let seed = BattleRng::random_seed();
+    // separate stream so the sickness rolls don't mirror the battle's first draws
+    game_state.apply_sickness(sick, &mut BattleRng::new(seed ^ SICKNESS_SEED_SALT));
     battle_start_events.send(BattleStartEvent {
         config: BattleConfig {
             friendly_units,
             friendly_modifiers,
+            friendly_unit_modifiers,
             friendly_banner: Banner::Player,
-            enemy_units: game_state.quest.enemy_unit_composition(),
-            enemy_modifiers: game_state.quest.enemy_modifiers(),
-            enemy_banner: match game_state.quest.war_chef {
-                0 => Banner::WarChef1,
-                1 => Banner::WarChef2,
-                2 => Banner::WarChef3,
-                3 => Banner::WarChef4,
-                _ => Banner::WarChef5,
+            friendly_formation: if game_state.formation.is_empty() {
+                None
+            } else {
+                Some(game_state.formation.clone())
             },
+            enemy_units: game_state.quest.enemy_unit_composition(&campaign),
+            enemy_modifiers: game_state.quest.enemy_modifiers(&campaign),
+            enemy_banner: game_state.quest.enemy_banner(&campaign),
+            enemy_formation: None,
+            victory_conditions: game_state.quest.victory_conditions(&campaign),
+            intervention_budget: INTERVENTION_BUDGET,
+            seed,
         },
         sandbox: false,
     });
@@ -104,16 +122,19 @@ fn game_director_change_state(
     mut planning_ended_events: EventReader<PlanningEndedEvent>,
     mut next_state: ResMut<NextState<AppState>>,
     mut game_state: ResMut<GameState>,
+    mut intermission_state: ResMut<IntermissionState>,
     game_director: Query<&GameDirector>,
+    campaign: Res<Campaign>,
 ) {
     if game_director.get_single().is_ok() {
         for battle_ended_event in battle_ended_events.iter() {
-            if battle_ended_event.report.victory {
+            if battle_ended_event.report.victory() {
                 game_state
                     .available_army
-                    .subtract_units(&battle_ended_event.report.dead_units);
-                if game_state.quest.next() {
+                    .subtract_units(&battle_ended_event.report.team(Team::Friendly).killed());
+                if game_state.quest.next(&campaign) {
                     game_state.checkpoint();
+                    intermission_state.show_report(battle_ended_event.report.clone());
                     next_state.set(AppState::GameIntermission);
                 } else {
                     next_state.set(AppState::GameOutro);
@@ -128,7 +149,7 @@ fn game_director_change_state(
                 game_state.checkpoint();
                 next_state.set(AppState::GameRewind);
             } else if planning_ended_event.skip {
-                game_state.quest.next();
+                game_state.quest.next(&campaign);
                 game_state.checkpoint();
                 next_state.set(AppState::GameIntermission);
             } else {
@@ -5,7 +5,10 @@ use enum_map::EnumMap;
 use rand::prelude::*;
 use strum::IntoEnumIterator;
 
-use crate::{AssetLibrary, Intel, Inventory, Item, Quest, UnitComposition, UnitKind};
+use crate::{
+    AssetLibrary, Formation, Intel, Inventory, Item, ItemDefinitions, Quest, UnitComposition,
+    UnitKind,
+};
 
 #[derive(Resource, Clone)]
 pub struct GameState {
@@ -19,7 +22,9 @@ pub struct GameState {
     pub inventory: Inventory,
     pub used_items: Vec<Item>,
     pub consumed_items: Vec<Item>,
+    pub fed_items: EnumMap<UnitKind, Vec<Item>>,
     pub loot: Loot,
+    pub formation: Formation,
     pub checkpoint: Option<Box<GameState>>,
 }
 
@@ -42,7 +47,9 @@ impl Default for GameState {
             inventory: Inventory::default(),
             used_items: vec![],
             consumed_items: vec![],
+            fed_items: EnumMap::default(),
             loot: Loot::default(),
+            formation: Formation::default(),
             checkpoint: None,
         }
     }
@@ -52,14 +59,14 @@ impl Default for GameState {
 pub struct Loot {
     food: isize,
     units: EnumMap<UnitKind, isize>,
-    items: EnumMap<Item, isize>,
+    items: Vec<(Item, isize)>,
 }
 
 impl Loot {
     pub fn reset(&mut self) {
         self.food = 0;
         self.units = EnumMap::default();
-        self.items = EnumMap::default();
+        self.items = vec![];
     }
 
     pub fn add_food(&mut self, food: isize) {
@@ -67,14 +74,22 @@ impl Loot {
     }
 
     pub fn add_items(&mut self, item: Item, count: isize) {
-        self.items[item] += count;
+        if let Some(entry) = self.items.iter_mut().find(|(i, _)| *i == item) {
+            entry.1 += count;
+        } else {
+            self.items.push((item, count));
+        }
     }
 
     pub fn add_units(&mut self, unit: UnitKind, count: isize) {
         self.units[unit] += count;
     }
 
-    pub fn summary(&self, asset_library: &AssetLibrary) -> Vec<TextSection> {
+    pub fn summary(
+        &self,
+        asset_library: &AssetLibrary,
+        item_definitions: &ItemDefinitions,
+    ) -> Vec<TextSection> {
         let mut loot_entries = vec![];
         if self.food != 0 {
             loot_entries.push(("Food", self.food));
@@ -84,9 +99,9 @@ impl Loot {
                 loot_entries.push((unit.name_plural(), self.units[unit]));
             }
         }
-        for item in Item::iter() {
-            if self.items[item] != 0 {
-                loot_entries.push((item.name(), self.items[item]));
+        for (item, count) in self.items.iter() {
+            if *count != 0 {
+                loot_entries.push((item_definitions.name(item), *count));
             }
         }
 
@@ -157,9 +172,7 @@ impl GameState {
         fed_army
     }
 
-    pub fn apply_sickness(&mut self, sick: bool) {
-        let mut rng = thread_rng();
-
+    pub fn apply_sickness(&mut self, sick: EnumMap<UnitKind, bool>, rng: &mut impl Rng) {
         // undo previous sickness
         for unit_kind in UnitKind::iter() {
             let count = self.available_army.get_count(unit_kind);
@@ -168,8 +181,8 @@ impl GameState {
             self.sick_army.set_count(unit_kind, 0);
         }
         // apply new sickness
-        if sick {
-            for unit_kind in UnitKind::iter() {
+        for unit_kind in UnitKind::iter() {
+            if sick[unit_kind] {
                 let count = self.available_army.get_count(unit_kind);
                 let sick = rng.gen_range(0..=(count / 2)).max(2).min(count.max(1) - 1);
                 self.available_army.set_count(unit_kind, count - sick);
@@ -1,7 +1,10 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, sprite::Anchor};
 use bevy_spine::prelude::*;
 
-use crate::{AppState, Articy, AssetLibrary, Depth, Dialogue, GameState, Transform2};
+use crate::{
+    AppState, Articy, AssetLibrary, BattleReport, Campaign, Clickable, ClickableSystem,
+    CollisionShape, Depth, Dialogue, GameState, ItemDefinitions, Sfx, SfxKind, Transform2,
+};
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
 pub enum IntermissionSystem {
@@ -15,44 +18,71 @@ pub struct IntermissionPlugin;
 impl Plugin for IntermissionPlugin {
     fn build(&self, app: &mut App) {
         if app.world.contains_resource::<State<AppState>>() {
-            app.add_system(
-                intermission_enter
-                    .in_schedule(OnEnter(AppState::GameIntermission))
-                    .in_set(IntermissionSystem::Enter),
-            )
-            .add_system(
-                intermission_spine_ready
-                    .in_set(IntermissionSystem::SpineReady)
-                    .in_set(SpineSet::OnReady),
-            )
-            .add_system(
-                intermission_update
-                    .run_if(in_state(AppState::GameIntermission))
-                    .in_set(IntermissionSystem::Update),
-            );
+            app.init_resource::<IntermissionState>()
+                .add_system(
+                    intermission_enter
+                        .in_schedule(OnEnter(AppState::GameIntermission))
+                        .in_set(IntermissionSystem::Enter),
+                )
+                .add_system(
+                    intermission_spine_ready
+                        .in_set(IntermissionSystem::SpineReady)
+                        .in_set(SpineSet::OnReady),
+                )
+                .add_system(
+                    intermission_update
+                        .run_if(in_state(AppState::GameIntermission))
+                        .in_set(IntermissionSystem::Update)
+                        .after(ClickableSystem),
+                );
         }
     }
 }
 
+#[derive(Resource, Default)]
+pub struct IntermissionState {
+    report: Option<BattleReport>,
+}
+
+impl IntermissionState {
+    pub fn show_report(&mut self, report: BattleReport) {
+        self.report = Some(report);
+    }
+}
+
 #[derive(Component)]
 struct CauldronSpine;
 
+#[derive(Component)]
+struct ContinueButton;
+
 fn intermission_enter(
     mut dialogue: ResMut<Dialogue>,
     mut game_state: ResMut<GameState>,
+    mut intermission_state: ResMut<IntermissionState>,
     mut commands: Commands,
     articy: Res<Articy>,
     asset_library: Res<AssetLibrary>,
+    campaign: Res<Campaign>,
+    item_definitions: Res<ItemDefinitions>,
 ) {
+    let food_reward = game_state.quest.food_reward(&campaign);
     game_state.loot.reset();
-    game_state.loot.add_food(15);
-    game_state.food += 15;
+    game_state.loot.add_food(food_reward as isize);
+    game_state.food += food_reward;
     for used_item in game_state.used_items.clone().iter() {
-        if let Some(script) = game_state.quest.item_script(*used_item, articy.as_ref()) {
+        if let Some(script) =
+            game_state
+                .quest
+                .item_script(used_item, &campaign, &item_definitions, articy.as_ref())
+        {
             dialogue.queue(script, game_state.as_mut());
         }
     }
-    if let Some(script) = game_state.quest.preplanning_script(articy.as_ref()) {
+    if let Some(script) = game_state
+        .quest
+        .preplanning_script(&campaign, articy.as_ref())
+    {
         dialogue.queue(script, game_state.as_mut());
     }
     commands.spawn((
@@ -81,6 +111,60 @@ fn intermission_enter(
         Depth::Exact(0.01),
         CauldronSpine,
     ));
+    // the battle that was just won stays up until the player moves on
+    if let Some(report) = intermission_state.report.take() {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_sections(vec![
+                    TextSection {
+                        value: "Battle Report\n".to_owned(),
+                        style: TextStyle {
+                            font: asset_library.font_heading.clone(),
+                            font_size: 96.,
+                            color: Color::WHITE,
+                        },
+                    },
+                    TextSection {
+                        value: report.summary(),
+                        style: TextStyle {
+                            font: asset_library.font_normal.clone(),
+                            font_size: 32.,
+                            color: Color::WHITE,
+                        },
+                    },
+                ])
+                .with_alignment(TextAlignment::Left),
+                text_anchor: Anchor::TopLeft,
+                ..Default::default()
+            },
+            Transform2::from_xy(-1200., 660.),
+            Depth::Exact(0.2),
+        ));
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    "Continue",
+                    TextStyle {
+                        font: asset_library.font_normal.clone(),
+                        font_size: 56.,
+                        color: Color::GRAY,
+                    },
+                )
+                .with_alignment(TextAlignment::Center),
+                ..Default::default()
+            },
+            Clickable {
+                shape: CollisionShape::Rect {
+                    offset: Vec2::ZERO,
+                    size: Vec2::new(300., 70.),
+                },
+                ..Default::default()
+            },
+            Transform2::from_xy(-1000., -600.),
+            Depth::Exact(0.2),
+            ContinueButton,
+        ));
+    }
 }
 
 fn intermission_spine_ready(
@@ -99,8 +183,34 @@ fn intermission_spine_ready(
     }
 }
 
-fn intermission_update(mut next_state: ResMut<NextState<AppState>>, dialogue: Res<Dialogue>) {
-    if !dialogue.active() {
+fn intermission_update(
+    mut continue_button_query: Query<(&mut Text, &Clickable), With<ContinueButton>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut sfx: ResMut<Sfx>,
+    dialogue: Res<Dialogue>,
+) {
+    if dialogue.active() {
+        return;
+    }
+    let mut waiting = false;
+    for (mut continue_button_text, continue_button_clickable) in continue_button_query.iter_mut() {
+        waiting = true;
+        if continue_button_clickable.just_hovered() {
+            sfx.play(SfxKind::UiButtonHover);
+        }
+        if let Some(section) = continue_button_text.sections.get_mut(0) {
+            section.style.color = if continue_button_clickable.hovered {
+                Color::WHITE
+            } else {
+                Color::GRAY
+            };
+        }
+        if continue_button_clickable.confirmed {
+            sfx.play(SfxKind::UiButtonConfirm);
+            waiting = false;
+        }
+    }
+    if !waiting {
         next_state.set(AppState::GamePlanning);
     }
 }
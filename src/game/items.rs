@@ -1,91 +1,144 @@
-use enum_map::Enum;
-use strum_macros::EnumIter;
-
-use crate::BattleModifier;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Enum, EnumIter)]
-pub enum Item {
-    CracklingMoss,
-    SquirtBlopBerries,
-    FiremanderSalts,
-    AxeShrooms,
-    BogHardWeeds,
-    CeleryQuartz,
-    FrostyWebStrands,
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{AssetLibrary, BattleModifier, BattleModifiers};
+
+pub struct ItemsPlugin;
+
+impl Plugin for ItemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ItemDefinitions>();
+        if app.world.contains_resource::<AssetLibrary>() {
+            app.add_asset::<ItemDefinitions>()
+                .init_asset_loader::<ItemDefinitionsLoader>()
+                .add_system(item_definitions_reload);
+        }
+    }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Item(String);
+
 impl Item {
-    pub fn modifiers(&self) -> Vec<BattleModifier> {
-        match self {
-            Self::CracklingMoss => vec![BattleModifier::QuickAttack, BattleModifier::Cowardly],
-            Self::SquirtBlopBerries => vec![BattleModifier::Wet, BattleModifier::Slowness],
-            Self::FiremanderSalts => vec![BattleModifier::Fire, BattleModifier::Combustion],
-            Self::AxeShrooms => vec![BattleModifier::ExtraAttack, BattleModifier::FriendlyFire],
-            Self::BogHardWeeds => vec![BattleModifier::ExtraDefense, BattleModifier::Sickness],
-            Self::CeleryQuartz => vec![BattleModifier::ExtraSpeed, BattleModifier::Explosive],
-            Self::FrostyWebStrands => vec![BattleModifier::Ice, BattleModifier::Blindness],
-        }
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
     }
 
-    pub fn name(&self) -> &'static str {
-        match self {
-            Self::CracklingMoss => "Crackling Moss",
-            Self::SquirtBlopBerries => "Squirt Blop-Berries",
-            Self::FiremanderSalts => "Firemander Salts",
-            Self::AxeShrooms => "Axe Shrooms",
-            Self::BogHardWeeds => "Bog Hard-Weeds",
-            Self::CeleryQuartz => "Celery Quartz",
-            Self::FrostyWebStrands => "Frosty Web-Strands",
-        }
+    pub fn id(&self) -> &str {
+        &self.0
     }
+}
 
-    pub fn skin_name(&self) -> &'static str {
-        match self {
-            Self::CracklingMoss => "CracklingMoss",
-            Self::SquirtBlopBerries => "SquirtBlopBerries",
-            Self::FiremanderSalts => "FiremanderSalts",
-            Self::AxeShrooms => "AxeShrooms",
-            Self::BogHardWeeds => "BogHardWeeds",
-            Self::CeleryQuartz => "CeleryQuartz",
-            Self::FrostyWebStrands => "FrostyWebStrands",
-        }
+#[derive(Clone, Deserialize)]
+pub struct ItemDefinition {
+    pub id: Item,
+    pub name: String,
+    pub skin_name: String,
+    pub icon_index: usize,
+    pub modifiers: Vec<BattleModifier>,
+    pub positive_effect: String,
+    pub side_effect: String,
+    #[serde(default)]
+    pub first_use_dialogue: Option<String>,
+    #[serde(default)]
+    pub used_variable: Option<String>,
+}
+
+#[derive(Resource, Clone, Deserialize, TypeUuid)]
+#[uuid = "c4a8e2f1-6b3d-4e95-8a07-1d5f9c2b7e63"]
+pub struct ItemDefinitions {
+    items: Vec<ItemDefinition>,
+}
+
+impl Default for ItemDefinitions {
+    fn default() -> Self {
+        ron::from_str(include_str!("../../assets/spices.items.ron")).unwrap()
     }
+}
 
-    pub fn index(&self) -> usize {
-        match self {
-            Self::CracklingMoss => 0,
-            Self::SquirtBlopBerries => 1,
-            Self::FiremanderSalts => 2,
-            Self::AxeShrooms => 3,
-            Self::BogHardWeeds => 4,
-            Self::CeleryQuartz => 5,
-            Self::FrostyWebStrands => 6,
-        }
+impl ItemDefinitions {
+    pub fn iter(&self) -> impl Iterator<Item = &ItemDefinition> {
+        self.items.iter()
     }
 
-    pub fn positive_effect(&self) -> &'static str {
-        match self {
-            Self::CracklingMoss => "Attack more quickly.",
-            Self::SquirtBlopBerries => "Become wet and resist fire.",
-            Self::FiremanderSalts => "Fire damage burns non-armored enemies.",
-            Self::AxeShrooms => "All attacks deal more damage.",
-            Self::BogHardWeeds => "Increase defense.",
-            Self::CeleryQuartz => "Increase movement speed.",
-            Self::FrostyWebStrands => {
-                "Ice damage slows enemies and is effective\nagainst wet enemies."
+    pub fn get(&self, item: &Item) -> Option<&ItemDefinition> {
+        self.items.iter().find(|definition| definition.id == *item)
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<&ItemDefinition> {
+        self.items.iter().find(|definition| definition.name == name)
+    }
+
+    pub fn name<'a>(&'a self, item: &'a Item) -> &'a str {
+        self.get(item)
+            .map(|definition| definition.name.as_str())
+            .unwrap_or(item.id())
+    }
+
+    pub fn modifiers(&self, item: &Item) -> Vec<BattleModifier> {
+        self.get(item)
+            .map(|definition| definition.modifiers.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn battle_modifiers<'a>(
+        &self,
+        items: impl IntoIterator<Item = &'a Item>,
+    ) -> BattleModifiers {
+        let mut battle_modifiers = BattleModifiers::default();
+        for item in items {
+            for modifier in self.modifiers(item) {
+                battle_modifiers.add_stack(modifier);
             }
         }
+        battle_modifiers
+    }
+}
+
+#[derive(Default)]
+struct ItemDefinitionsLoader;
+
+impl AssetLoader for ItemDefinitionsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let item_definitions: ItemDefinitions = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(item_definitions));
+            Ok(())
+        })
     }
 
-    pub fn side_effect(&self) -> &'static str {
-        match self {
-            Self::CracklingMoss => "Units may run from battle.",
-            Self::SquirtBlopBerries => "Units become slow.",
-            Self::FiremanderSalts => "Units may spontaneously combust.",
-            Self::AxeShrooms => "Units may attack friendlies.",
-            Self::BogHardWeeds => "Some units become sick and unusable \nnext battle.",
-            Self::CeleryQuartz => "Units may explode.",
-            Self::FrostyWebStrands => "Units become blind and attack\nrandomly.",
+    fn extensions(&self) -> &[&str] {
+        &["items.ron"]
+    }
+}
+
+fn item_definitions_reload(
+    mut item_definitions: ResMut<ItemDefinitions>,
+    mut asset_events: EventReader<AssetEvent<ItemDefinitions>>,
+    item_definitions_assets: Res<Assets<ItemDefinitions>>,
+    asset_library: Res<AssetLibrary>,
+) {
+    for asset_event in asset_events.iter() {
+        match asset_event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                if *handle == asset_library.items {
+                    if let Some(asset) = item_definitions_assets.get(handle) {
+                        *item_definitions = asset.clone();
+                        info!("reloaded item definitions");
+                    }
+                }
+            }
+            AssetEvent::Removed { .. } => {}
         }
     }
 }
@@ -108,10 +161,10 @@ impl Inventory {
         self.items.remove(index);
     }
 
-    pub fn remove_last(&mut self, item: Item) {
+    pub fn remove_last(&mut self, item: &Item) {
         let mut i = self.items.len() - 1;
         loop {
-            if self.items[i] == item {
+            if self.items[i] == *item {
                 self.remove(i);
                 break;
             } else if i == 0 {
@@ -126,7 +179,7 @@ impl Inventory {
         &self.items
     }
 
-    pub fn count(&self, item: Item) -> usize {
-        self.items.iter().filter(|i| **i == item).count()
+    pub fn count(&self, item: &Item) -> usize {
+        self.items.iter().filter(|i| *i == item).count()
     }
 }
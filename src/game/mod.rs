@@ -1,4 +1,5 @@
 mod battle;
+mod campaign;
 mod dialogues;
 mod game;
 mod game_director;
@@ -19,6 +20,7 @@ mod start;
 mod team;
 
 pub use battle::*;
+pub use campaign::*;
 pub use dialogues::*;
 pub use game::*;
 pub use game_director::*;
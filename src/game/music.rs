@@ -5,7 +5,7 @@ use bevy_audio_plus::source::AudioPlusSource;
 use bevy_kira_audio::{AudioApp, AudioChannel, AudioControl, AudioInstance, AudioTween};
 use lerp::Lerp;
 
-use crate::{AddFixedEvent, AppState, AssetLibrary, Persistent};
+use crate::{AddFixedEvent, AppState, AssetLibrary, BattleState, BattleTime, Persistent};
 
 pub struct MusicPlugin;
 
@@ -61,9 +61,11 @@ fn music_controller(
     intro_channel: Res<AudioChannel<IntroMusic>>,
     app_state: Res<State<AppState>>,
     asset_library: Res<AssetLibrary>,
+    battle_state: Res<BattleState>,
+    battle_time: Res<BattleTime>,
     time: Res<Time>,
 ) {
-    let dt = time.delta_seconds_f64().min(0.5);
+    let dt = time.raw_delta_seconds_f64().min(0.5);
 
     let should_play = app_state.0.is_game_state();
     if should_play {
@@ -132,6 +134,9 @@ fn music_controller(
         target_volume *= 0.1;
         target_playback_rate = 0.95;
     }
+    if app_state.0 == AppState::GameBattle && battle_state.battling() {
+        target_playback_rate *= battle_time.time_scale() as f64;
+    }
     local.volume = local.volume.lerp(
         target_volume,
         dt * if local.jingle_time > 0. { 5. } else { 1. },
@@ -4,10 +4,11 @@ use bevy_spine::prelude::*;
 use strum::IntoEnumIterator;
 
 use crate::{
-    typewriter_text, AddFixedEvent, Articy, AssetLibrary, Clickable, ClickableSystem,
-    CollisionShape, Depth, Dialogue, GameState, InteractionMode, InteractionSet, InteractionStack,
-    Item, PersistentGameState, Script, SecondOrder, Sfx, SfxKind, SpawnSet, SpineSpawnSet,
-    TargetTransform, Transform2, UnitKind, UpdateSet,
+    typewriter_text, AddFixedEvent, Articy, AssetLibrary, Campaign, Clickable, ClickableSystem,
+    CollisionShape, Cursor, Depth, DepthLayer, Dialogue, GameState, InteractionMode,
+    InteractionSet, InteractionStack, Item, ItemDefinitions, PersistentGameState, Script,
+    SecondOrder, Sfx, SfxKind, SpawnSet, SpineSpawnSet, TargetTransform, Transform2,
+    UnitDefinitions, UnitKind, UpdateSet, FORMATION_LANES, FORMATION_ROWS,
 };
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
@@ -17,11 +18,14 @@ pub enum PlanningSystem {
     SpineReady,
     SpineEvents,
     UpdateButtonsAndInfo,
+    UpdateDraggedItem,
     UpdateUnitCountText,
     UpdateFoodCountText,
     UpdateItems,
     UpdateUnitComp,
     StartBattle,
+    SpawnFormation,
+    UpdateFormation,
     Ui,
 }
 
@@ -58,11 +62,23 @@ impl Plugin for PlanningPlugin {
                     .after(ClickableSystem)
                     .after(InteractionSet),
             )
+            .add_system(
+                planning_update_dragged_item
+                    .in_set(PlanningSystem::UpdateDraggedItem)
+                    .after(ClickableSystem)
+                    .after(InteractionSet),
+            )
             .add_system(planning_update_unit_count_text.in_set(PlanningSystem::UpdateUnitCountText))
             .add_system(planning_update_food_count_text.in_set(PlanningSystem::UpdateFoodCountText))
             .add_system(planning_update_items.in_set(PlanningSystem::UpdateItems))
             .add_system(planning_update_unit_comp.in_set(PlanningSystem::UpdateUnitComp))
             .add_system(planning_start_battle.in_set(PlanningSystem::StartBattle))
+            .add_system(planning_spawn_formation.in_set(PlanningSystem::SpawnFormation))
+            .add_system(
+                planning_update_formation
+                    .in_set(PlanningSystem::UpdateFormation)
+                    .after(ClickableSystem),
+            )
             .add_system(planning_ui.in_set(PlanningSystem::Ui));
     }
 }
@@ -70,9 +86,11 @@ impl Plugin for PlanningPlugin {
 #[derive(Resource)]
 pub struct PlanningState {
     planning: bool,
+    formation: bool,
     start: bool,
     skip: bool,
     rewind: bool,
+    dragged_item: Option<Item>,
 }
 
 impl PlanningState {
@@ -85,9 +103,11 @@ impl Default for PlanningState {
     fn default() -> Self {
         Self {
             planning: false,
+            formation: false,
             start: false,
             skip: false,
             rewind: false,
+            dragged_item: None,
         }
     }
 }
@@ -111,7 +131,7 @@ struct PlanningButton {
     pub kind: PlanningButtonKind,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 enum PlanningButtonKind {
     Item(Item),
     Unit(UnitKind),
@@ -136,6 +156,9 @@ struct PlanningStartBattle;
 #[derive(Component)]
 struct PlanningItem(usize);
 
+#[derive(Component)]
+struct PlanningDraggedItem;
+
 #[derive(Component)]
 struct PlanningHint;
 
@@ -147,6 +170,27 @@ struct PlanningUnitComp {
     count: usize,
 }
 
+#[derive(Component)]
+struct PlanningFormation;
+
+#[derive(Component)]
+struct PlanningFormationCell {
+    row: usize,
+    lane: usize,
+}
+
+#[derive(Component)]
+struct PlanningFormationCellText {
+    row: usize,
+    lane: usize,
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum PlanningFormationButton {
+    Fight,
+    Back,
+}
+
 struct AddUnitCompEvent(UnitKind);
 
 fn planning_start(
@@ -207,6 +251,16 @@ fn planning_start(
             PlanningSpine,
             SpineSync,
         ));
+        commands.spawn((
+            SpriteSheetBundle {
+                texture_atlas: asset_library.image_atlas_planning_buttons.clone(),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            Transform2::default().with_scale(Vec2::splat(0.5)),
+            Depth::Exact(0.9),
+            PlanningDraggedItem,
+        ));
     }
 }
 
@@ -216,13 +270,18 @@ fn planning_spine_ready(
     mut commands: Commands,
     asset_library: Res<AssetLibrary>,
     game_state: Res<GameState>,
+    item_definitions: Res<ItemDefinitions>,
 ) {
     for spine_ready_event in spine_ready_events.iter() {
         if let Ok(mut spine) = spine_query.get_mut(spine_ready_event.entity) {
             let _ = spine
                 .animation_state
                 .set_animation_by_name(0, "cauldron", true);
-            if !game_state.loot.summary(asset_library.as_ref()).is_empty() {
+            if !game_state
+                .loot
+                .summary(asset_library.as_ref(), &item_definitions)
+                .is_empty()
+            {
                 let _ = spine
                     .animation_state
                     .set_animation_by_name(3, "loot", false);
@@ -535,7 +594,9 @@ fn planning_spine_ready(
                         parent.spawn((
                             Text2dBundle {
                                 text: Text::from_sections(
-                                    game_state.loot.summary(asset_library.as_ref()),
+                                    game_state
+                                        .loot
+                                        .summary(asset_library.as_ref(), &item_definitions),
                                 )
                                 .with_alignment(TextAlignment::Center),
                                 text_anchor: Anchor::TopCenter,
@@ -581,9 +642,11 @@ fn planning_update(
             _private: (),
         });
         planning_state.planning = false;
+        planning_state.formation = false;
         planning_state.start = false;
         planning_state.skip = false;
         planning_state.rewind = false;
+        planning_state.dragged_item = None;
     }
 }
 
@@ -600,6 +663,9 @@ fn planning_update_buttons_and_info(
     sick_query: Query<Entity, With<PlanningSick>>,
     asset_library: Res<AssetLibrary>,
     articy: Res<Articy>,
+    unit_definitions: Res<UnitDefinitions>,
+    campaign: Res<Campaign>,
+    item_definitions: Res<ItemDefinitions>,
 ) {
     let mut info_text = None;
     let header_style = TextStyle {
@@ -619,9 +685,12 @@ fn planning_update_buttons_and_info(
     };
     for (button_entity, mut button_sprite, button) in button_query.iter_mut() {
         if let Ok(button_clickable) = clickable_query.get(button_entity) {
-            let active = match button.kind {
-                PlanningButtonKind::Unit(unit) => game_state.available_army.get_count(unit) > 0,
-                PlanningButtonKind::Item(..) => game_state.consumed_items.is_empty(),
+            let active = match &button.kind {
+                PlanningButtonKind::Unit(unit) => game_state.available_army.get_count(*unit) > 0,
+                PlanningButtonKind::Item(item) => game_state
+                    .consumed_items
+                    .iter()
+                    .all(|consumed_item| consumed_item == item),
                 PlanningButtonKind::Noop => false,
             };
             if active {
@@ -646,11 +715,24 @@ fn planning_update_buttons_and_info(
                 Color::rgba(0.5, 0.5, 0.5, 0.9)
             };
             if button_clickable.hovered {
-                match button.kind {
+                match &button.kind {
                     PlanningButtonKind::Item(item) => {
+                        let Some(item_definition) = item_definitions.get(item) else {
+                            continue;
+                        };
+                        let cauldron_modifiers = item_definitions
+                            .battle_modifiers(game_state.consumed_items.iter().chain([item]));
+                        let cauldron_effects = item_definition
+                            .modifiers
+                            .iter()
+                            .map(|modifier| {
+                                modifier.describe(cauldron_modifiers.get_stacks(*modifier))
+                            })
+                            .collect::<Vec<_>>()
+                            .join(", ");
                         info_text = Some(vec![
                             TextSection {
-                                value: format!("{}\n", item.name()),
+                                value: format!("{}\n", item_definition.name),
                                 style: header_style.clone(),
                             },
                             TextSection {
@@ -658,7 +740,7 @@ fn planning_update_buttons_and_info(
                                 style: bold_style.clone(),
                             },
                             TextSection {
-                                value: format!("{}\n", item.positive_effect()),
+                                value: format!("{}\n", item_definition.positive_effect),
                                 style: description_style.clone(),
                             },
                             TextSection {
@@ -666,7 +748,15 @@ fn planning_update_buttons_and_info(
                                 style: bold_style.clone(),
                             },
                             TextSection {
-                                value: format!("{}", item.side_effect()),
+                                value: format!("{}\n", item_definition.side_effect),
+                                style: description_style.clone(),
+                            },
+                            TextSection {
+                                value: format!("In Cauldron: "),
+                                style: bold_style.clone(),
+                            },
+                            TextSection {
+                                value: cauldron_effects,
                                 style: description_style.clone(),
                             },
                         ])
@@ -686,19 +776,40 @@ fn planning_update_buttons_and_info(
                                 style: bold_style.clone(),
                             },
                             TextSection {
-                                value: format!("{}", unit.stats().cost),
+                                value: format!("{}", unit_definitions.unit_stats(*unit).cost),
                                 style: description_style.clone(),
                             },
-                        ])
+                        ]);
+                        let fed_items = &game_state.fed_items[*unit];
+                        if let (Some(info_text), Some(fed_item)) =
+                            (info_text.as_mut(), fed_items.first())
+                        {
+                            info_text.push(TextSection {
+                                value: format!("\nSpice: "),
+                                style: bold_style.clone(),
+                            });
+                            info_text.push(TextSection {
+                                value: format!(
+                                    "{} x{}",
+                                    item_definitions.name(fed_item),
+                                    fed_items.len()
+                                ),
+                                style: description_style.clone(),
+                            });
+                        }
                     }
                     PlanningButtonKind::Noop => {}
                 }
             }
             if button_clickable.confirmed && active {
-                match button.kind {
+                match button.kind.clone() {
                     PlanningButtonKind::Item(item) => {
+                        let skin_name = item_definitions
+                            .get(&item)
+                            .map(|item_definition| item_definition.skin_name.as_str())
+                            .unwrap_or(item.id());
                         for mut planning_spine in planning_spine_query.iter_mut() {
-                            let _ = planning_spine.skeleton.set_skin_by_name(item.skin_name());
+                            let _ = planning_spine.skeleton.set_skin_by_name(skin_name);
                             let _ = planning_spine.animation_state.set_animation_by_name(
                                 1,
                                 "insert_spices",
@@ -706,11 +817,11 @@ fn planning_update_buttons_and_info(
                             );
                         }
                         sfx.play(SfxKind::CauldronAddSpice);
+                        game_state.inventory.remove_last(&item);
                         game_state.consumed_items.push(item);
-                        game_state.inventory.remove_last(item);
                     }
                     PlanningButtonKind::Unit(unit_kind) => {
-                        let unit_cost = unit_kind.stats().cost;
+                        let unit_cost = unit_definitions.unit_stats(unit_kind).cost;
                         if game_state.available_army.get_count(unit_kind) > 0
                             && game_state.food >= unit_cost
                         {
@@ -746,16 +857,30 @@ fn planning_update_buttons_and_info(
     for hint_entity in hint_query.iter() {
         if let Ok(hint_clickable) = clickable_query.get(hint_entity) {
             if hint_clickable.clicked {
-                info_text = Some(vec![
+                let mut sections = vec![
                     TextSection {
                         value: "Battle Hint\n".to_owned(),
                         style: header_style.clone(),
                     },
                     TextSection {
-                        value: typewriter_text(game_state.quest.hint(), 999, false),
+                        value: typewriter_text(game_state.quest.hint(&campaign), 999, false),
                         style: description_style.clone(),
                     },
-                ])
+                ];
+                let victory_conditions = game_state.quest.victory_conditions(&campaign);
+                if !victory_conditions.is_empty() {
+                    sections.push(TextSection {
+                        value: "\n\nObjectives:".to_owned(),
+                        style: description_style.clone(),
+                    });
+                    for victory_condition in victory_conditions.iter() {
+                        sections.push(TextSection {
+                            value: format!("\n- {}", victory_condition.describe()),
+                            style: description_style.clone(),
+                        });
+                    }
+                }
+                info_text = Some(sections);
             } else if hint_clickable.hovered {
                 info_text = Some(vec![
                     TextSection {
@@ -814,6 +939,71 @@ fn planning_update_buttons_and_info(
     }
 }
 
+// spices dropped on a unit's bowl only apply to that unit kind, a click still adds them to the cauldron
+fn planning_update_dragged_item(
+    mut planning_state: ResMut<PlanningState>,
+    mut game_state: ResMut<GameState>,
+    mut sfx: ResMut<Sfx>,
+    mut dragged_item_query: Query<
+        (&mut Transform2, &mut Visibility, &mut TextureAtlasSprite),
+        With<PlanningDraggedItem>,
+    >,
+    mut planning_spine_query: Query<&mut Spine, With<PlanningSpine>>,
+    button_query: Query<(&PlanningButton, &Clickable)>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    cursor: Res<Cursor>,
+    item_definitions: Res<ItemDefinitions>,
+) {
+    let mut hovered_unit = None;
+    for (button, button_clickable) in button_query.iter() {
+        match &button.kind {
+            PlanningButtonKind::Item(item) => {
+                if button_clickable.just_clicked() {
+                    planning_state.dragged_item = Some(item.clone());
+                }
+            }
+            PlanningButtonKind::Unit(unit_kind) => {
+                if button_clickable.hovered {
+                    hovered_unit = Some(*unit_kind);
+                }
+            }
+            PlanningButtonKind::Noop => {}
+        }
+    }
+    if !mouse_buttons.pressed(MouseButton::Left) {
+        if let (Some(item), Some(unit_kind)) = (planning_state.dragged_item.take(), hovered_unit) {
+            if game_state.inventory.count(&item) > 0
+                && game_state.fed_items[unit_kind]
+                    .iter()
+                    .all(|fed_item| *fed_item == item)
+            {
+                for mut planning_spine in planning_spine_query.iter_mut() {
+                    let _ = planning_spine
+                        .animation_state
+                        .set_animation_by_name(1, "food_eat", false);
+                }
+                sfx.play(SfxKind::CauldronAddSpice);
+                game_state.inventory.remove_last(&item);
+                game_state.fed_items[unit_kind].push(item);
+            }
+        }
+    }
+    for (mut dragged_item_transform, mut dragged_item_visibility, mut dragged_item_sprite) in
+        dragged_item_query.iter_mut()
+    {
+        dragged_item_transform.translation = cursor.position;
+        if let Some(item) = planning_state.dragged_item.as_ref() {
+            *dragged_item_visibility = Visibility::Inherited;
+            dragged_item_sprite.index = item_definitions
+                .get(item)
+                .map(|item_definition| item_definition.icon_index)
+                .unwrap_or(0);
+        } else {
+            *dragged_item_visibility = Visibility::Hidden;
+        }
+    }
+}
+
 fn planning_update_unit_count_text(
     mut unit_count_text_query: Query<(&mut Text, &PlanningUnitCountText)>,
     game_state: Res<GameState>,
@@ -848,11 +1038,12 @@ fn planning_update_items(
     )>,
     mut text_query: Query<&mut Text>,
     game_state: Res<GameState>,
+    item_definitions: Res<ItemDefinitions>,
 ) {
     let mut items = vec![];
     for item in game_state.inventory.items() {
         if !items.contains(item) {
-            items.push(*item);
+            items.push(item.clone());
         }
     }
     for (mut item_button, mut item_visibility, _, item_children) in item_query.iter_mut() {
@@ -871,13 +1062,16 @@ fn planning_update_items(
     {
         for (slot_index, item) in items.clone().into_iter().enumerate() {
             if slot_index == planning_item.0 {
-                item_button.kind = PlanningButtonKind::Item(item);
-                item_button.image_index = item.index();
+                item_button.kind = PlanningButtonKind::Item(item.clone());
+                item_button.image_index = item_definitions
+                    .get(&item)
+                    .map(|item_definition| item_definition.icon_index)
+                    .unwrap_or(0);
                 *item_visibility = Visibility::Inherited;
                 for child in item_children.iter() {
                     if let Ok(mut text) = text_query.get_mut(*child) {
                         if let Some(section) = text.sections.get_mut(0) {
-                            section.value = format!("x{}", game_state.inventory.count(item));
+                            section.value = format!("x{}", game_state.inventory.count(&item));
                         }
                     }
                 }
@@ -961,7 +1155,236 @@ fn planning_start_battle(
                 );
             } else {
                 sfx.play(SfxKind::UiButtonConfirm);
-                planning_state.start = true;
+                planning_state.formation = true;
+            }
+        }
+    }
+}
+
+fn planning_spawn_formation(
+    mut commands: Commands,
+    mut interaction_stack: ResMut<InteractionStack>,
+    planning_state: Res<PlanningState>,
+    formation_query: Query<Entity, With<PlanningFormation>>,
+    asset_library: Res<AssetLibrary>,
+) {
+    interaction_stack.set_wants_interaction(InteractionMode::Formation, planning_state.formation);
+    if !planning_state.formation {
+        for formation_entity in formation_query.iter() {
+            commands.entity(formation_entity).despawn_recursive();
+        }
+        return;
+    }
+    if !formation_query.is_empty() {
+        return;
+    }
+    const CELL_SIZE: Vec2 = Vec2::new(360., 220.);
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(0., 0., 0., 0.8),
+                    custom_size: Some(Vec2::new(2560., 1440.)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Transform2::default(),
+            Depth::from(DepthLayer::Foreground(0.)),
+            PlanningFormation,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text2dBundle {
+                    text: Text::from_sections(vec![
+                        TextSection {
+                            value: "Formation\n".to_owned(),
+                            style: TextStyle {
+                                font: asset_library.font_heading.clone(),
+                                font_size: 128.,
+                                color: Color::WHITE,
+                            },
+                        },
+                        TextSection {
+                            value: "Click a spot to choose who stands there. Rats without a spot are scattered.".to_owned(),
+                            style: TextStyle {
+                                font: asset_library.font_normal.clone(),
+                                font_size: 42.,
+                                color: Color::WHITE,
+                            },
+                        },
+                    ])
+                    .with_alignment(TextAlignment::Center),
+                    text_anchor: Anchor::TopCenter,
+                    ..Default::default()
+                },
+                Transform2::from_xy(0., 640.),
+                Depth::Inherit(0.01),
+            ));
+            for row in 0..FORMATION_ROWS {
+                // the army marches right, so the front row is drawn rightmost
+                let x = (1. - row as f32) * (CELL_SIZE.x + 20.);
+                parent.spawn((
+                    Text2dBundle {
+                        text: Text::from_section(
+                            match row {
+                                0 => "Front",
+                                r if r == FORMATION_ROWS - 1 => "Back",
+                                _ => "Middle",
+                            },
+                            TextStyle {
+                                font: asset_library.font_bold.clone(),
+                                font_size: 52.,
+                                color: Color::WHITE,
+                            },
+                        )
+                        .with_alignment(TextAlignment::Center),
+                        ..Default::default()
+                    },
+                    Transform2::from_xy(x, 330.),
+                    Depth::Inherit(0.01),
+                ));
+                for lane in 0..FORMATION_LANES {
+                    let y = 120. - lane as f32 * (CELL_SIZE.y + 20.);
+                    parent.spawn((
+                        SpriteBundle {
+                            sprite: Sprite {
+                                custom_size: Some(CELL_SIZE),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        Clickable {
+                            shape: CollisionShape::Rect {
+                                offset: Vec2::ZERO,
+                                size: CELL_SIZE,
+                            },
+                            interaction_mode: InteractionMode::Formation,
+                            ..Default::default()
+                        },
+                        Transform2::from_xy(x, y),
+                        Depth::Inherit(0.01),
+                        PlanningFormationCell { row, lane },
+                    ));
+                    parent.spawn((
+                        Text2dBundle {
+                            text: Text::from_section(
+                                "",
+                                TextStyle {
+                                    font: asset_library.font_bold.clone(),
+                                    font_size: 52.,
+                                    color: Color::WHITE,
+                                },
+                            )
+                            .with_alignment(TextAlignment::Center),
+                            ..Default::default()
+                        },
+                        Transform2::from_xy(x, y),
+                        Depth::Inherit(0.02),
+                        PlanningFormationCellText { row, lane },
+                    ));
+                }
+            }
+            for (button, label, x) in [
+                (PlanningFormationButton::Back, "Back", -300.),
+                (PlanningFormationButton::Fight, "Fight!", 300.),
+            ] {
+                parent.spawn((
+                    Text2dBundle {
+                        text: Text::from_section(
+                            label,
+                            TextStyle {
+                                font: asset_library.font_heading.clone(),
+                                font_size: 96.,
+                                color: Color::GRAY,
+                            },
+                        )
+                        .with_alignment(TextAlignment::Center),
+                        ..Default::default()
+                    },
+                    Clickable {
+                        shape: CollisionShape::Rect {
+                            offset: Vec2::ZERO,
+                            size: Vec2::new(300., 100.),
+                        },
+                        interaction_mode: InteractionMode::Formation,
+                        ..Default::default()
+                    },
+                    Transform2::from_xy(x, -560.),
+                    Depth::Inherit(0.01),
+                    button,
+                ));
+            }
+        });
+}
+
+fn planning_update_formation(
+    mut planning_state: ResMut<PlanningState>,
+    mut game_state: ResMut<GameState>,
+    mut sfx: ResMut<Sfx>,
+    mut cell_query: Query<(&mut Sprite, &Clickable, &PlanningFormationCell)>,
+    mut cell_text_query: Query<(&mut Text, &PlanningFormationCellText)>,
+    mut button_query: Query<
+        (&mut Text, &Clickable, &PlanningFormationButton),
+        Without<PlanningFormationCellText>,
+    >,
+) {
+    if !planning_state.formation {
+        return;
+    }
+    for (mut cell_sprite, cell_clickable, cell) in cell_query.iter_mut() {
+        if cell_clickable.just_hovered() {
+            sfx.play(SfxKind::UiButtonHover);
+        }
+        if cell_clickable.confirmed {
+            sfx.play(SfxKind::UiButtonClick);
+            let mut options = vec![None];
+            for unit_kind in UnitKind::iter() {
+                if game_state.fed_army.get_count(unit_kind) > 0 {
+                    options.push(Some(unit_kind));
+                }
+            }
+            let current = game_state.formation.get(cell.row, cell.lane);
+            let next = options
+                .iter()
+                .position(|option| *option == current)
+                .map(|index| (index + 1) % options.len())
+                .unwrap_or(0);
+            game_state.formation.set(cell.row, cell.lane, options[next]);
+        }
+        cell_sprite.color = if cell_clickable.hovered {
+            Color::rgba(1., 1., 1., 0.3)
+        } else {
+            Color::rgba(1., 1., 1., 0.15)
+        };
+    }
+    for (mut cell_text, cell_text_info) in cell_text_query.iter_mut() {
+        if let Some(section) = cell_text.sections.get_mut(0) {
+            section.value = match game_state
+                .formation
+                .get(cell_text_info.row, cell_text_info.lane)
+            {
+                Some(unit_kind) => unit_kind.name_plural().to_owned(),
+                None => "-".to_owned(),
+            };
+        }
+    }
+    for (mut button_text, button_clickable, button) in button_query.iter_mut() {
+        if button_clickable.just_hovered() {
+            sfx.play(SfxKind::UiButtonHover);
+        }
+        if let Some(section) = button_text.sections.get_mut(0) {
+            section.style.color = if button_clickable.hovered {
+                Color::WHITE
+            } else {
+                Color::GRAY
+            };
+        }
+        if button_clickable.confirmed {
+            sfx.play(SfxKind::UiButtonConfirm);
+            match button {
+                PlanningFormationButton::Fight => planning_state.start = true,
+                PlanningFormationButton::Back => planning_state.formation = false,
             }
         }
     }
@@ -974,13 +1397,15 @@ fn planning_ui(
     mut dialogue: ResMut<Dialogue>,
     interaction_stack: Res<InteractionStack>,
     articy: Res<Articy>,
+    unit_definitions: Res<UnitDefinitions>,
+    item_definitions: Res<ItemDefinitions>,
 ) {
     if planning_state.planning && interaction_stack.can_interact(InteractionMode::Game) && false {
         egui::Window::new("Planning").show(contexts.ctx_mut(), |ui| {
             ui.label(format!("Food remaining: {}", game_state.food));
 
             for unit_kind in UnitKind::iter() {
-                let unit_cost = unit_kind.stats().cost;
+                let unit_cost = unit_definitions.unit_stats(unit_kind).cost;
                 if ui
                     .button(format!(
                         "Feed {} ({} available, {} ready, {} sick, cost: {})",
@@ -1010,7 +1435,10 @@ fn planning_ui(
                 for (item_index, item) in
                     game_state.inventory.items().clone().into_iter().enumerate()
                 {
-                    if ui.button(format!("Use {}", item.name())).clicked() {
+                    if ui
+                        .button(format!("Use {}", item_definitions.name(&item)))
+                        .clicked()
+                    {
                         game_state.consumed_items.push(item);
                         remove_item = Some(item_index);
                     }
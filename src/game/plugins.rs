@@ -1,10 +1,11 @@
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
 use crate::{
-    AreaOfEffectTargetingPlugin, BannerPlugin, BattlePlugin, BattleSplashPlugin, BattlefieldPlugin,
-    DamagePlugin, FeelerPlugin, GameDirectorPlugin, GamePlugin, HealthPlugin, IntermissionPlugin,
-    IntroPlugin, MusicPlugin, OutroPlugin, PlanningPlugin, ProjectilePlugin, RewindPlugin,
-    SandboxPlugin, SfxPlugin, SpineAttackPlugin, SpineFxPlugin, StartPlugin, UnitPlugin,
+    BannerPlugin, BattleCameraPlugin, BattleOverlayPlugin, BattleSimulationPlugin,
+    BattleSplashPlugin, BattleTimePlugin, BattlefieldPlugin, CampaignPlugin, GameDirectorPlugin,
+    GamePlugin, IntermissionPlugin, InterventionControlsPlugin, IntroPlugin, ItemsPlugin,
+    MusicPlugin, OutroPlugin, PlanningPlugin, RewindPlugin, SandboxPlugin, SfxPlugin,
+    SpineFxPlugin, StartPlugin,
 };
 
 pub struct GamePlugins;
@@ -15,6 +16,8 @@ impl PluginGroup for GamePlugins {
 
         group = group.add(GamePlugin);
         group = group.add(GameDirectorPlugin);
+        group = group.add(CampaignPlugin);
+        group = group.add(ItemsPlugin);
         group = group.add(MusicPlugin);
         group = group.add(SfxPlugin);
 
@@ -31,18 +34,15 @@ impl PluginGroup for GamePlugins {
         group = group.add(PlanningPlugin);
 
         // battle
-        group = group.add(BattlePlugin);
+        group = group.add(BattleSimulationPlugin);
         group = group.add(BattlefieldPlugin);
-        group = group.add(UnitPlugin);
-        group = group.add(DamagePlugin);
-        group = group.add(HealthPlugin);
-        group = group.add(SpineAttackPlugin);
         group = group.add(SpineFxPlugin);
-        group = group.add(ProjectilePlugin);
-        group = group.add(AreaOfEffectTargetingPlugin);
-        group = group.add(FeelerPlugin);
         group = group.add(BattleSplashPlugin);
         group = group.add(BannerPlugin);
+        group = group.add(BattleTimePlugin);
+        group = group.add(BattleCameraPlugin);
+        group = group.add(BattleOverlayPlugin);
+        group = group.add(InterventionControlsPlugin);
 
         // rewind
         group = group.add(RewindPlugin);
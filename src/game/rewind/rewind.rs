@@ -4,8 +4,9 @@ use bevy::{prelude::*, sprite::Anchor};
 use strum::IntoEnumIterator;
 
 use crate::{
-    AppState, Articy, AssetLibrary, Clickable, ClickableSystem, Depth, Dialogue, GameState,
-    PersistentGameState, Script, Sfx, SfxKind, Transform2, UnitKind,
+    AppState, Articy, AssetLibrary, BattleState, Clickable, ClickableSystem, Depth, Dialogue,
+    GameState, ItemDefinitions, PersistentGameState, ReplayState, SandboxState, Script, Sfx,
+    SfxKind, Transform2, UnitKind,
 };
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
@@ -15,6 +16,7 @@ pub enum RewindSystem {
     UpdateButton,
     UpdateButtonText,
     UpdateBattleInfo,
+    UpdateReplayButton,
 }
 
 pub struct RewindPlugin;
@@ -39,7 +41,12 @@ impl Plugin for RewindPlugin {
                         .after(ClickableSystem),
                 )
                 .add_system(rewind_update_button_text.in_set(RewindSystem::UpdateButtonText))
-                .add_system(rewind_update_battle_info.in_set(RewindSystem::UpdateBattleInfo));
+                .add_system(rewind_update_battle_info.in_set(RewindSystem::UpdateBattleInfo))
+                .add_system(
+                    rewind_update_replay_button
+                        .in_set(RewindSystem::UpdateReplayButton)
+                        .after(ClickableSystem),
+                );
         }
     }
 }
@@ -65,6 +72,9 @@ struct RewindText;
 #[derive(Component)]
 struct BattleInfo;
 
+#[derive(Component)]
+struct ReplayButton;
+
 fn rewind_enter(
     mut commands: Commands,
     mut rewind_state: ResMut<RewindState>,
@@ -73,6 +83,7 @@ fn rewind_enter(
     mut dialogue: ResMut<Dialogue>,
     asset_library: Res<AssetLibrary>,
     articy: Res<Articy>,
+    replay_state: Res<ReplayState>,
 ) {
     *rewind_state = RewindState::default();
     rewind_state.stages = vec![];
@@ -181,6 +192,32 @@ fn rewind_enter(
         Depth::Exact(0.1),
         BattleInfo,
     ));
+    if replay_state.last_replay().is_some() {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    "Watch Last Battle",
+                    TextStyle {
+                        font: asset_library.font_normal.clone(),
+                        font_size: 56.,
+                        color: Color::GRAY,
+                    },
+                )
+                .with_alignment(TextAlignment::Center),
+                ..Default::default()
+            },
+            Clickable {
+                shape: crate::CollisionShape::Rect {
+                    offset: Vec2::ZERO,
+                    size: Vec2::new(500., 70.),
+                },
+                ..Default::default()
+            },
+            Transform2::from_xy(590., -300.),
+            Depth::Exact(0.1),
+            ReplayButton,
+        ));
+    }
     let mut stage_index = 0;
     for y in 0..5 {
         let count = match y {
@@ -308,6 +345,8 @@ fn rewind_update_button_text(
 fn rewind_update_battle_info(
     mut battle_info_query: Query<&mut Text, With<BattleInfo>>,
     rewind_state: Res<RewindState>,
+    item_definitions: Res<ItemDefinitions>,
+    battle_state: Res<BattleState>,
 ) {
     if rewind_state.stages.len() == 0 {
         return;
@@ -327,10 +366,48 @@ fn rewind_update_battle_info(
             if !game_state.inventory.is_empty() {
                 info += &format!("\nItems:\n");
                 for item in game_state.inventory.items() {
-                    info += &format!("{}\n", item.name());
+                    info += &format!("{}\n", item_definitions.name(item));
+                }
+            }
+            if rewind_state.selected == rewind_state.stages.len() - 1 {
+                if let Some(report) = battle_state.last_report() {
+                    info += &format!("\nLast Battle: {}", report.summary());
                 }
             }
             section.value = info;
         }
     }
 }
+
+fn rewind_update_replay_button(
+    mut replay_button_query: Query<(&mut Text, &Clickable), With<ReplayButton>>,
+    mut replay_state: ResMut<ReplayState>,
+    mut sandbox_state: ResMut<SandboxState>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut sfx: ResMut<Sfx>,
+    dialogue: Res<Dialogue>,
+) {
+    if dialogue.active() {
+        return;
+    }
+    for (mut replay_button_text, replay_button_clickable) in replay_button_query.iter_mut() {
+        if replay_button_clickable.just_hovered() {
+            sfx.play(SfxKind::UiButtonHover);
+        }
+        if let Some(section) = replay_button_text.sections.get_mut(0) {
+            section.style.color = if replay_button_clickable.hovered {
+                Color::WHITE
+            } else {
+                Color::GRAY
+            };
+        }
+        if replay_button_clickable.confirmed {
+            if let Some(replay) = replay_state.last_replay().cloned() {
+                sfx.play(SfxKind::UiButtonConfirm);
+                replay_state.play(replay);
+                sandbox_state.set_exit_state(AppState::GameRewind);
+                next_state.set(AppState::Sandbox);
+            }
+        }
+    }
+}
@@ -4,7 +4,9 @@ use std::fs::write;
 
 use crate::{
     cleanup_non_persistent_entities, AppState, BattleConfig, BattleModifier, BattleModifiers,
-    BattleStartEvent, BattleState, EventSet, UnitComposition, UnitKind,
+    BattleOverlaySettings, BattleRng, BattleStartEvent, BattleState, BattleTime, DamageKind,
+    EventSet, Formation, Replay, ReplayState, Team, UnitComposition, UnitKind, VictoryCondition,
+    BATTLE_SPEEDS, FORMATION_LANES, FORMATION_ROWS,
 };
 use bevy::prelude::*;
 use bevy_egui::{
@@ -28,6 +30,8 @@ impl Plugin for SandboxPlugin {
                 cleanup: false,
                 start_battle: false,
                 battle_config,
+                replay_error: None,
+                exit_state: AppState::MainMenu,
             })
             .add_system(sandbox_pre_update.in_base_set(CoreSet::PreUpdate))
             .add_system(
@@ -51,6 +55,14 @@ pub struct SandboxState {
     cleanup: bool,
     start_battle: bool,
     battle_config: BattleConfig,
+    replay_error: Option<String>,
+    exit_state: AppState,
+}
+
+impl SandboxState {
+    pub fn set_exit_state(&mut self, exit_state: AppState) {
+        self.exit_state = exit_state;
+    }
 }
 
 fn sandbox_pre_update(mut example_state: ResMut<SandboxState>) {
@@ -77,9 +89,12 @@ fn sandbox_fixed_update(
 fn sandbox_ui(
     mut contexts: EguiContexts,
     mut sandbox_state: ResMut<SandboxState>,
+    mut replay_state: ResMut<ReplayState>,
+    mut battle_time: ResMut<BattleTime>,
+    mut battle_overlay_settings: ResMut<BattleOverlaySettings>,
     battle_state: Res<BattleState>,
 ) {
-    if !battle_state.battling() {
+    if !battle_state.battling() && !replay_state.playing() {
         egui::Window::new("Battle").show(contexts.ctx_mut(), |ui| {
             fn unit_composition_ui(ui: &mut Ui, unit_composition: &mut UnitComposition) {
                 for unit_kind in UnitKind::iter() {
@@ -99,9 +114,107 @@ fn sandbox_ui(
             }
             fn battle_modifiers_ui(ui: &mut Ui, battle_modifiers: &mut BattleModifiers) {
                 for battle_modifier in BattleModifier::iter() {
-                    let mut checked = battle_modifiers[battle_modifier];
-                    ui.checkbox(&mut checked, battle_modifier.name());
-                    battle_modifiers[battle_modifier] = checked;
+                    ui.horizontal(|ui| {
+                        let mut stacks = battle_modifiers.get_stacks(battle_modifier);
+                        ui.add(egui::DragValue::new(&mut stacks).clamp_range(0..=5));
+                        battle_modifiers.set_stacks(battle_modifier, stacks);
+                        ui.label(battle_modifier.name());
+                    });
+                }
+            }
+            fn formation_ui(ui: &mut Ui, id: &str, formation: &mut Option<Formation>) {
+                let mut enabled = formation.is_some();
+                ui.checkbox(&mut enabled, "Use Formation");
+                if enabled != formation.is_some() {
+                    *formation = enabled.then(Formation::default);
+                }
+                if let Some(formation) = formation {
+                    egui::Grid::new(id).show(ui, |ui| {
+                        for lane in 0..FORMATION_LANES {
+                            for row in 0..FORMATION_ROWS {
+                                let mut cell = formation.get(row, lane);
+                                egui::ComboBox::from_id_source((id, row, lane))
+                                    .selected_text(
+                                        cell.map(|unit_kind| unit_kind.name_plural())
+                                            .unwrap_or("-"),
+                                    )
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut cell, None, "-");
+                                        for unit_kind in UnitKind::iter() {
+                                            ui.selectable_value(
+                                                &mut cell,
+                                                Some(unit_kind),
+                                                unit_kind.name_plural(),
+                                            );
+                                        }
+                                    });
+                                formation.set(row, lane, cell);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                }
+            }
+            fn victory_conditions_ui(ui: &mut Ui, victory_conditions: &mut Vec<VictoryCondition>) {
+                let mut remove = None;
+                for (index, victory_condition) in victory_conditions.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_source(("victory_condition", index))
+                            .selected_text(victory_condition.name())
+                            .show_ui(ui, |ui| {
+                                for option in [
+                                    VictoryCondition::Annihilate,
+                                    VictoryCondition::Survive { seconds: 30. },
+                                    VictoryCondition::KillTarget {
+                                        unit_kind: UnitKind::Brute,
+                                    },
+                                    VictoryCondition::ProtectBanner,
+                                    VictoryCondition::CaptureBanner,
+                                    VictoryCondition::LimitLosses { max_losses: 5 },
+                                ] {
+                                    let selected = victory_condition.name() == option.name();
+                                    if ui.selectable_label(selected, option.name()).clicked()
+                                        && !selected
+                                    {
+                                        *victory_condition = option;
+                                    }
+                                }
+                            });
+                        match victory_condition {
+                            VictoryCondition::Survive { seconds } => {
+                                ui.add(egui::DragValue::new(seconds).clamp_range(0..=600));
+                            }
+                            VictoryCondition::KillTarget { unit_kind } => {
+                                egui::ComboBox::from_id_source(("victory_condition_target", index))
+                                    .selected_text(unit_kind.name_plural())
+                                    .show_ui(ui, |ui| {
+                                        for option in UnitKind::iter() {
+                                            ui.selectable_value(
+                                                unit_kind,
+                                                option,
+                                                option.name_plural(),
+                                            );
+                                        }
+                                    });
+                            }
+                            VictoryCondition::LimitLosses { max_losses } => {
+                                ui.add(egui::DragValue::new(max_losses).clamp_range(0..=100));
+                            }
+                            _ => {}
+                        }
+                        if ui.button("Remove").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = remove {
+                    victory_conditions.remove(index);
+                }
+                if victory_conditions.is_empty() {
+                    ui.label(VictoryCondition::Annihilate.describe());
+                }
+                if ui.button("Add Condition").clicked() {
+                    victory_conditions.push(VictoryCondition::Annihilate);
                 }
             }
 
@@ -130,6 +243,59 @@ fn sandbox_ui(
 
             ui.add_space(16.);
 
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label("Friendly Formation (front row first)");
+                    formation_ui(
+                        ui,
+                        "friendly_formation",
+                        &mut sandbox_state.battle_config.friendly_formation,
+                    );
+                });
+                ui.vertical(|ui| {
+                    ui.label("Enemy Formation (front row first)");
+                    formation_ui(
+                        ui,
+                        "enemy_formation",
+                        &mut sandbox_state.battle_config.enemy_formation,
+                    );
+                });
+            });
+
+            ui.add_space(16.);
+
+            ui.label("Victory Conditions");
+            victory_conditions_ui(ui, &mut sandbox_state.battle_config.victory_conditions);
+
+            ui.add_space(16.);
+
+            ui.horizontal(|ui| {
+                ui.label("Seed");
+                ui.add(egui::DragValue::new(&mut sandbox_state.battle_config.seed));
+                if ui.button("Randomize").clicked() {
+                    sandbox_state.battle_config.seed = BattleRng::random_seed();
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Interventions");
+                ui.add(egui::DragValue::new(
+                    &mut sandbox_state.battle_config.intervention_budget,
+                ));
+            });
+
+            ui.add_space(16.);
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut battle_overlay_settings.health_bars, "Health Bars");
+                ui.checkbox(
+                    &mut battle_overlay_settings.damage_numbers,
+                    "Damage Numbers",
+                );
+            });
+
+            ui.add_space(16.);
+
             ui.horizontal(|ui| {
                 #[cfg(not(target_arch = "wasm32"))]
                 if ui.button("Save").clicked() {
@@ -144,17 +310,160 @@ fn sandbox_ui(
                     sandbox_state.battle_config = BattleConfig::default();
                 }
             });
+            ui.add_space(16.);
+
+            if let Some(battle_config) =
+                replay_ui(ui, &mut replay_state, &mut sandbox_state.replay_error)
+            {
+                sandbox_state.battle_config = battle_config;
+                sandbox_state.cleanup = true;
+            }
+
             ui.add_space(16.);
             if ui.button("Start Battle").clicked() {
                 sandbox_state.cleanup = true;
                 sandbox_state.start_battle = true;
             }
         });
+        if let Some(report) = battle_state.last_report() {
+            egui::Window::new("Last Battle").show(contexts.ctx_mut(), |ui| {
+                ui.label(format!(
+                    "{} in {} ticks",
+                    report.outcome.name(),
+                    report.ticks
+                ));
+                for (team, name) in [(Team::Friendly, "Friendly"), (Team::Enemy, "Enemy")] {
+                    ui.add_space(16.);
+                    ui.label(name);
+                    egui::Grid::new(name).striped(true).show(ui, |ui| {
+                        ui.label("");
+                        for heading in [
+                            "Spawned",
+                            "Killed",
+                            "Retreated",
+                            "Alive",
+                            "Routed",
+                            "Rallied",
+                        ] {
+                            ui.label(heading);
+                        }
+                        for damage_kind in DamageKind::iter() {
+                            ui.label(format!("{} Dealt", damage_kind.name()));
+                        }
+                        for damage_kind in DamageKind::iter() {
+                            ui.label(format!("{} Taken", damage_kind.name()));
+                        }
+                        for heading in ["Friendly Fire", "Combustion Kills", "Explosion Kills"] {
+                            ui.label(heading);
+                        }
+                        ui.end_row();
+                        for unit_kind in UnitKind::iter() {
+                            let unit_report = &report.team(team).units[unit_kind];
+                            ui.label(unit_kind.name_plural());
+                            ui.label(unit_report.spawned.to_string());
+                            ui.label(unit_report.killed.to_string());
+                            ui.label(unit_report.retreated.to_string());
+                            ui.label(unit_report.alive.to_string());
+                            ui.label(unit_report.routed.to_string());
+                            ui.label(unit_report.rallied.to_string());
+                            for damage_kind in DamageKind::iter() {
+                                ui.label(format!("{:.0}", unit_report.damage_dealt[damage_kind]));
+                            }
+                            for damage_kind in DamageKind::iter() {
+                                ui.label(format!(
+                                    "{:.0}",
+                                    unit_report.damage_received[damage_kind]
+                                ));
+                            }
+                            ui.label(format!("{:.0}", unit_report.friendly_fire_damage));
+                            ui.label(unit_report.combustion_kills.to_string());
+                            ui.label(unit_report.explosion_kills.to_string());
+                            ui.end_row();
+                        }
+                    });
+                }
+            });
+        }
+    } else if battle_state.battling() {
+        egui::Window::new("Battle Controls").show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                let paused = battle_time.paused();
+                if ui.selectable_label(paused, "Pause").clicked() {
+                    battle_time.set_paused(!paused);
+                }
+                for (index, speed) in BATTLE_SPEEDS.iter().enumerate() {
+                    if ui
+                        .selectable_label(
+                            !paused && battle_time.speed_index() == index,
+                            format!("{}x", speed),
+                        )
+                        .clicked()
+                    {
+                        battle_time.set_speed(index);
+                        battle_time.set_paused(false);
+                    }
+                }
+                if ui.button("Step").clicked() {
+                    battle_time.set_paused(true);
+                    battle_time.step();
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut battle_overlay_settings.health_bars, "Health Bars");
+                ui.checkbox(
+                    &mut battle_overlay_settings.damage_numbers,
+                    "Damage Numbers",
+                );
+            });
+        });
     }
 }
 
+// shared with the battle sandbox example, returns the config of a replay that was just queued
+pub fn replay_ui(
+    ui: &mut Ui,
+    replay_state: &mut ReplayState,
+    replay_error: &mut Option<String>,
+) -> Option<BattleConfig> {
+    let mut battle_config = None;
+    ui.horizontal(|ui| {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(replay) = replay_state.last_replay() {
+            if ui.button("Save Replay").clicked() {
+                write("replay.ron", ron::to_string(replay).unwrap()).unwrap();
+            }
+        }
+
+        if ui.button("Play Replay").clicked() {
+            match read_to_string("replay.ron") {
+                Ok(file_contents) => match Replay::from_ron(&file_contents) {
+                    Ok(replay) => {
+                        battle_config = Some(replay.battle_config());
+                        replay_state.play(replay);
+                        *replay_error = None;
+                    }
+                    Err(error) => *replay_error = Some(error.to_string()),
+                },
+                Err(error) => {
+                    *replay_error = Some(format!("Replay could not be opened: {}", error))
+                }
+            }
+        }
+
+        if let Some(replay_error) = replay_error.as_ref() {
+            ui.label(replay_error);
+        } else if let Some(divergence) = replay_state.divergence() {
+            ui.label(format!("Replay diverged at tick {}", divergence.tick));
+        } else if replay_state.finished() {
+            ui.label("Replay matched");
+        }
+    });
+    battle_config
+}
+
 fn sandbox_exit(
     mut battle_state: ResMut<BattleState>,
+    mut sandbox_state: ResMut<SandboxState>,
     mut next_app_state: ResMut<NextState<AppState>>,
     keys: Res<Input<KeyCode>>,
 ) {
@@ -162,6 +471,8 @@ fn sandbox_exit(
         battle_state.stop();
     }
     if keys.just_pressed(KeyCode::Escape) {
-        next_app_state.set(AppState::MainMenu);
+        battle_state.stop();
+        next_app_state.set(sandbox_state.exit_state);
+        sandbox_state.exit_state = AppState::MainMenu;
     }
 }
@@ -1,3 +1,4 @@
+use bevy::prelude::*;
 use enum_map::Enum;
 use strum_macros::EnumIter;
 
@@ -31,6 +32,13 @@ impl Team {
         }
     }
 
+    pub fn banner_position(&self) -> Vec2 {
+        match self {
+            Self::Friendly => Vec2::new(-950., -270.),
+            Self::Enemy => Vec2::new(850., -270.),
+        }
+    }
+
     pub fn opposite_team(&self) -> Team {
         match self {
             Self::Friendly => Self::Enemy,
@@ -13,16 +13,24 @@ fn main() {
     App::new()
         .insert_resource(Msaa::Off)
         .insert_resource(ClearColor(Color::rgb(0.1, 0.1, 0.1)))
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Battle for Rattoria".to_owned(),
-                resolution: (1280., 768.).into(),
-                canvas: Some("#bevy".to_owned()),
-                fit_canvas_to_parent: true,
-                ..default()
-            }),
-            ..default()
-        }))
+        .add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Battle for Rattoria".to_owned(),
+                        resolution: (1280., 768.).into(),
+                        canvas: Some("#bevy".to_owned()),
+                        fit_canvas_to_parent: true,
+                        ..default()
+                    }),
+                    ..default()
+                })
+                .set(AssetPlugin {
+                    // lets the unit stats be tweaked while a debug build is running
+                    watch_for_changes: cfg!(debug_assertions),
+                    ..default()
+                }),
+        )
         .add_plugin(AppStatePlugin)
         .add_plugin(AssetLibraryPlugin)
         .add_plugins(CommonPlugins)